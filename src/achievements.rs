@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Total standing hours celebrated as milestones, in order.
+const MILESTONE_HOURS: [u64; 6] = [10, 50, 100, 250, 500, 1000];
+
+/// Streaks of days meeting the standing goal and standing milestones, over the whole history.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Achievements {
+    /// Consecutive days the goal was met, up to today, or up to yesterday while today's goal is
+    /// not met yet. Only tracked with a standing goal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_streak: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_streak: Option<u32>,
+    pub standing_seconds: u64,
+    pub milestones: Vec<Milestone>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Milestone {
+    pub hours: u64,
+    pub reached_on: NaiveDate,
+}
+
+impl Achievements {
+    /// Computes the achievements from the standing time of every day, as of `today`.
+    pub fn compute(
+        days: &BTreeMap<NaiveDate, Duration>,
+        goal: Option<Duration>,
+        today: NaiveDate,
+    ) -> Self {
+        let mut standing = Duration::ZERO;
+        let mut milestones = Vec::new();
+
+        for (date, day_standing) in days {
+            standing += *day_standing;
+
+            while let Some(&hours) = MILESTONE_HOURS.get(milestones.len()) {
+                if standing < Duration::from_secs(hours * 3600) {
+                    break;
+                }
+                milestones.push(Milestone {
+                    hours,
+                    reached_on: *date,
+                });
+            }
+        }
+
+        let (current_streak, longest_streak) = match goal {
+            Some(goal) => {
+                let (current, longest) = streaks(days, goal, today);
+                (Some(current), Some(longest))
+            }
+            None => (None, None),
+        };
+
+        Self {
+            current_streak,
+            longest_streak,
+            standing_seconds: standing.as_secs(),
+            milestones,
+        }
+    }
+
+    /// Standing hours of the next milestone, `None` once they are all reached.
+    pub fn next_milestone(&self) -> Option<u64> {
+        MILESTONE_HOURS.get(self.milestones.len()).copied()
+    }
+}
+
+/// Length of a streak, such as "1 day" or "3 days".
+pub fn format_days(days: u32) -> String {
+    match days {
+        1 => "1 day".to_string(),
+        days => format!("{} days", days),
+    }
+}
+
+/// Current and longest streaks of days standing at least `goal`.
+fn streaks(days: &BTreeMap<NaiveDate, Duration>, goal: Duration, today: NaiveDate) -> (u32, u32) {
+    let met = |date: &NaiveDate| days.get(date).is_some_and(|standing| *standing >= goal);
+
+    let mut longest = 0;
+    let mut streak = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for date in days.keys().filter(|date| met(date)) {
+        streak = if previous.and_then(|previous| previous.succ_opt()) == Some(*date) {
+            streak + 1
+        } else {
+            1
+        };
+        longest = longest.max(streak);
+        previous = Some(*date);
+    }
+
+    // The goal of today can still be met, which does not break the streak
+    let last = if met(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    let current = last.map_or(0, |last| {
+        last.iter_days().rev().take_while(|date| met(date)).count() as u32
+    });
+
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 9, day).unwrap()
+    }
+
+    fn days(standing: &[(u32, u32)]) -> BTreeMap<NaiveDate, Duration> {
+        standing
+            .iter()
+            .map(|&(day, hours)| (date(day), HOUR * hours))
+            .collect()
+    }
+
+    #[test]
+    fn test_streaks() {
+        let days = days(&[(1, 2), (2, 3), (3, 2), (5, 2), (6, 1), (8, 2), (9, 2)]);
+
+        let achievements = Achievements::compute(&days, Some(HOUR * 2), date(10));
+
+        assert_eq!(achievements.current_streak, Some(2));
+        assert_eq!(achievements.longest_streak, Some(3));
+    }
+
+    #[test]
+    fn test_current_streak_broken() {
+        let days = days(&[(1, 2), (2, 2)]);
+
+        let achievements = Achievements::compute(&days, Some(HOUR * 2), date(4));
+
+        assert_eq!(achievements.current_streak, Some(0));
+        assert_eq!(achievements.longest_streak, Some(2));
+    }
+
+    #[test]
+    fn test_milestones() {
+        let days = days(&[(1, 8), (2, 8), (3, 40)]);
+
+        let achievements = Achievements::compute(&days, None, date(3));
+
+        assert_eq!(achievements.current_streak, None);
+        assert_eq!(achievements.standing_seconds, 56 * 3600);
+        assert_eq!(
+            achievements.milestones,
+            vec![
+                Milestone {
+                    hours: 10,
+                    reached_on: date(2)
+                },
+                Milestone {
+                    hours: 50,
+                    reached_on: date(3)
+                },
+            ]
+        );
+        assert_eq!(achievements.next_milestone(), Some(100));
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local, NaiveTime, TimeDelta, TimeZone};
+use ical::{parser::ical::component::IcalEvent, IcalParser};
+use serde::Deserialize;
+
+use crate::{
+    duration,
+    ics::{self, IcsError},
+    Message,
+};
+
+const DEFAULT_MARGIN: Duration = Duration::from_secs(120);
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Moves the changes to standing out of the meetings of the calendar files.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarAwareness {
+    /// iCalendar files, disabled when there are none.
+    pub files: Vec<PathBuf>,
+    pub on_conflict: Conflict,
+    /// How long before a meeting an advanced change happens.
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub margin: Duration,
+}
+
+impl Default for CalendarAwareness {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            on_conflict: Conflict::default(),
+            margin: DEFAULT_MARGIN,
+        }
+    }
+}
+
+/// Where a change to standing falling in a meeting is moved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Conflict {
+    /// To the end of the meeting
+    #[default]
+    Postpone,
+    /// Right before the meeting, or to its end once it started
+    Advance,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub summary: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agenda {
+    pub events: Vec<Event>,
+    on_conflict: Conflict,
+    margin: TimeDelta,
+}
+
+impl Agenda {
+    pub fn new(config: &CalendarAwareness) -> Self {
+        Self {
+            events: Vec::new(),
+            on_conflict: config.on_conflict,
+            margin: TimeDelta::from_std(config.margin).unwrap_or_default(),
+        }
+    }
+
+    fn busy_at(&self, time: DateTime<Local>) -> Option<&Event> {
+        self.events
+            .iter()
+            .find(|event| event.start <= time && time < event.end)
+    }
+
+    /// When a change to standing expected at `time` happens instead, at `now` or later.
+    pub fn reschedule(&self, now: DateTime<Local>, time: DateTime<Local>) -> DateTime<Local> {
+        if self.on_conflict == Conflict::Advance && self.busy_at(time).is_some() {
+            // Go back to the start of back to back meetings
+            let mut start = time;
+            while let Some(event) = self
+                .events
+                .iter()
+                .find(|event| event.start < start && start <= event.end)
+            {
+                start = event.start;
+            }
+
+            if start > now {
+                return (start - self.margin).max(now);
+            }
+        }
+
+        let mut end = time;
+        while let Some(event) = self.busy_at(end) {
+            end = event.end;
+        }
+
+        end
+    }
+
+    /// Meeting in progress at `now`, or the next one of the day.
+    pub fn upcoming(&self, now: DateTime<Local>) -> Option<&Event> {
+        self.events
+            .iter()
+            .filter(|event| event.end > now && event.start.date_naive() <= now.date_naive())
+            .min_by_key(|event| event.start)
+    }
+}
+
+#[derive(Debug)]
+pub enum CalendarError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, ical::parser::ParserError),
+}
+
+impl fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarError::Read(path, err) => {
+                write!(f, "could not read {}: {}", path.display(), err)
+            }
+            CalendarError::Parse(path, err) => {
+                write!(f, "could not parse {}: {}", path.display(), err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {}
+
+/// Meetings of the calendar files, and the events left out because they cannot be read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Loaded {
+    pub events: Vec<Event>,
+    pub skipped: Vec<(String, IcsError)>,
+}
+
+/// Loads the meetings of `files` from yesterday to tomorrow, sorted by start.
+pub fn load(files: &[PathBuf], now: DateTime<Local>) -> Result<Loaded, CalendarError> {
+    let today = now.date_naive().and_time(NaiveTime::MIN);
+    let day = |days| {
+        Local
+            .from_local_datetime(&(today + TimeDelta::days(days)))
+            .earliest()
+            .unwrap_or(now)
+    };
+    let (from, until) = (day(-1), day(2));
+
+    let mut loaded = Loaded::default();
+    for path in files {
+        let file = File::open(path).map_err(|err| CalendarError::Read(path.clone(), err))?;
+        let parsed = parse(BufReader::new(file), from, until)
+            .map_err(|err| CalendarError::Parse(path.clone(), err))?;
+        loaded.events.extend(parsed.events);
+        loaded.skipped.extend(parsed.skipped);
+    }
+    loaded.events.sort_by_key(|event| event.start);
+
+    Ok(loaded)
+}
+
+/// Reloads the meetings whenever a file or the day changes.
+pub fn spawn_monitor(files: Vec<PathBuf>, sender: Sender<Message>) {
+    thread::spawn(move || {
+        let mut loaded = None;
+
+        loop {
+            let now = Local::now();
+            let version = (now.date_naive(), modification_times(&files));
+
+            if loaded.as_ref() != Some(&version) {
+                let messages = match load(&files, now) {
+                    Ok(Loaded { events, skipped }) if !skipped.is_empty() => vec![
+                        Message::Meetings(events),
+                        Message::Error(format!(
+                            "Ignored calendar events: {}",
+                            skipped
+                                .iter()
+                                .map(|(summary, err)| format!("{} ({})", summary, err))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )),
+                    ],
+                    Ok(Loaded { events, .. }) => vec![Message::Meetings(events)],
+                    Err(err) => vec![Message::Error(format!(
+                        "Could not load the calendar: {}",
+                        err
+                    ))],
+                };
+                if messages
+                    .into_iter()
+                    .any(|message| sender.send(message).is_err())
+                {
+                    return;
+                }
+                loaded = Some(version);
+            }
+
+            thread::sleep(RELOAD_INTERVAL);
+        }
+    });
+}
+
+fn modification_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+fn parse<B: BufRead>(
+    reader: B,
+    from: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Result<Loaded, ical::parser::ParserError> {
+    let mut loaded = Loaded::default();
+
+    for calendar in IcalParser::new(reader) {
+        for event in &calendar?.events {
+            match meeting(event) {
+                Ok(Some(event)) if event.end > from && event.start < until => {
+                    loaded.events.push(event)
+                }
+                Ok(_) => {}
+                Err(err) => loaded.skipped.push((summary(event), err)),
+            }
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// Busy time of `event`, `None` when it is free, cancelled or lasts all day.
+fn meeting(event: &IcalEvent) -> Result<Option<Event>, IcsError> {
+    let value = |name| ics::value(&event.properties, name);
+
+    if value("STATUS") == Some("CANCELLED") || value("TRANSP") == Some("TRANSPARENT") {
+        return Ok(None);
+    }
+    // A moved occurrence, with a RECURRENCE-ID, is read as a single event
+    if value("RRULE").is_some() || value("RDATE").is_some() {
+        return Err(IcsError::Recurring);
+    }
+
+    let Some(start) = ics::property(&event.properties, "DTSTART")
+        .map(ics::date_time)
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    let end = match ics::property(&event.properties, "DTEND") {
+        Some(end) => ics::date_time(end)?,
+        None => value("DURATION")
+            .map(|text| {
+                ics::parse_duration(text)
+                    .map(|length| start + length)
+                    .ok_or_else(|| IcsError::Invalid("duration", text.to_string()))
+            })
+            .transpose()?,
+    };
+
+    Ok(end.filter(|end| *end > start).map(|end| Event {
+        summary: summary(event),
+        start,
+        end,
+    }))
+}
+
+fn summary(event: &IcalEvent) -> String {
+    ics::text(&event.properties, "SUMMARY").unwrap_or_else(|| "Busy".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use chrono_tz::America::Montreal;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2024-09-02 is a Monday
+        Local
+            .with_ymd_and_hms(2024, 9, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn parse_events(ics: &str) -> Vec<Event> {
+        parse(ics.as_bytes(), at(1, 0, 0), at(14, 0, 0))
+            .unwrap()
+            .events
+    }
+
+    fn agenda(on_conflict: Conflict, events: &[(DateTime<Local>, DateTime<Local>)]) -> Agenda {
+        Agenda {
+            events: events
+                .iter()
+                .map(|(start, end)| Event {
+                    summary: "Meeting".to_string(),
+                    start: *start,
+                    end: *end,
+                })
+                .collect(),
+            on_conflict,
+            margin: TimeDelta::minutes(2),
+        }
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:review\r\n\
+             SUMMARY:Design review\\, round 2\r\n\
+             DTSTART;TZID=America/Montreal:20240903T140000\r\n\
+             DTEND;TZID=America/Montreal:20240903T150000\r\n\
+             BEGIN:VALARM\r\n\
+             TRIGGER:-PT10M\r\n\
+             END:VALARM\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:lunch\r\n\
+             SUMMARY:Lunch\r\n\
+             TRANSP:TRANSPARENT\r\n\
+             DTSTART:20240903T120000\r\n\
+             DURATION:PT1H\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:holiday\r\n\
+             DTSTART;VALUE=DATE:20240902\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:call\r\n\
+             SUMMARY:Call\r\n\
+             DTSTART:20240904T100000\r\n\
+             DURATION:PT45M\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+
+        let montreal = |hour| {
+            Montreal
+                .with_ymd_and_hms(2024, 9, 3, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+        assert_eq!(
+            events,
+            vec![
+                Event {
+                    summary: "Design review, round 2".to_string(),
+                    start: montreal(14),
+                    end: montreal(15),
+                },
+                Event {
+                    summary: "Call".to_string(),
+                    start: at(4, 10, 0),
+                    end: at(4, 10, 45),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_recurring_events() {
+        let loaded = parse(
+            "BEGIN:VCALENDAR\n\
+             BEGIN:VEVENT\n\
+             UID:standup\n\
+             SUMMARY:Standup\n\
+             DTSTART:20240826T093000\n\
+             DTEND:20240826T094500\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             UID:standup\n\
+             RECURRENCE-ID:20240906T093000\n\
+             SUMMARY:Standup\n\
+             DTSTART:20240906T110000\n\
+             DTEND:20240906T111500\n\
+             END:VEVENT\n\
+             END:VCALENDAR\n"
+                .as_bytes(),
+            at(1, 0, 0),
+            at(14, 0, 0),
+        )
+        .unwrap();
+
+        let starts: Vec<DateTime<Local>> = loaded.events.iter().map(|event| event.start).collect();
+        assert_eq!(starts, vec![at(6, 11, 0)]);
+        assert_eq!(
+            loaded.skipped,
+            vec![("Standup".to_string(), IcsError::Recurring)]
+        );
+    }
+
+    #[test]
+    fn test_parse_events_in_time_zone() {
+        let loaded = parse(
+            "BEGIN:VCALENDAR\n\
+             BEGIN:VTIMEZONE\n\
+             TZID:America/Montreal\n\
+             BEGIN:STANDARD\n\
+             DTSTART:19700101T000000\n\
+             TZOFFSETFROM:+0000\n\
+             TZOFFSETTO:+0000\n\
+             END:STANDARD\n\
+             END:VTIMEZONE\n\
+             BEGIN:VEVENT\n\
+             UID:review\n\
+             SUMMARY:Design review\n\
+             DTSTART;TZID=America/Montreal:20241104T140000\n\
+             DURATION:PT1H\n\
+             END:VEVENT\n\
+             BEGIN:VEVENT\n\
+             UID:call\n\
+             SUMMARY:Call\n\
+             DTSTART;TZID=Eastern Standard Time:20241104T100000\n\
+             DURATION:PT30M\n\
+             END:VEVENT\n\
+             END:VCALENDAR\n"
+                .as_bytes(),
+            at(1, 0, 0),
+            at(1, 0, 0) + TimeDelta::days(90),
+        )
+        .unwrap();
+
+        // The time zone database wins over the VTIMEZONE of the file
+        let utc = |hour| {
+            Utc.with_ymd_and_hms(2024, 11, 4, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+        assert_eq!(
+            loaded.events,
+            vec![Event {
+                summary: "Design review".to_string(),
+                start: utc(19),
+                end: utc(20),
+            }]
+        );
+        assert_eq!(
+            loaded.skipped,
+            vec![(
+                "Call".to_string(),
+                IcsError::UnknownZone("Eastern Standard Time".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_reschedule_postpone() {
+        let agenda = agenda(
+            Conflict::Postpone,
+            &[(at(2, 10, 0), at(2, 11, 0)), (at(2, 11, 0), at(2, 11, 30))],
+        );
+
+        assert_eq!(agenda.reschedule(at(2, 9, 0), at(2, 9, 50)), at(2, 9, 50));
+        assert_eq!(agenda.reschedule(at(2, 9, 0), at(2, 10, 15)), at(2, 11, 30));
+    }
+
+    #[test]
+    fn test_reschedule_advance() {
+        let agenda = agenda(
+            Conflict::Advance,
+            &[(at(2, 10, 0), at(2, 11, 0)), (at(2, 11, 0), at(2, 11, 30))],
+        );
+
+        assert_eq!(agenda.reschedule(at(2, 9, 0), at(2, 11, 15)), at(2, 9, 58));
+        assert_eq!(agenda.reschedule(at(2, 9, 59), at(2, 10, 15)), at(2, 9, 59));
+        // Too late to stand before the meeting
+        assert_eq!(
+            agenda.reschedule(at(2, 10, 5), at(2, 10, 15)),
+            at(2, 11, 30)
+        );
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let agenda = agenda(
+            Conflict::Postpone,
+            &[(at(2, 10, 0), at(2, 11, 0)), (at(3, 9, 0), at(3, 10, 0))],
+        );
+
+        assert_eq!(agenda.upcoming(at(2, 9, 0)).unwrap().start, at(2, 10, 0));
+        assert_eq!(agenda.upcoming(at(2, 10, 30)).unwrap().start, at(2, 10, 0));
+        assert_eq!(agenda.upcoming(at(2, 11, 0)), None);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    config::{Config, ConfigError},
+    duration::parse_duration,
+    report::{ReportFormat, ReportPeriod},
+    statusbar::StatusFormat,
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Time spent sitting before the reminder to stand, such as 45m or 1h30m, rejected when stances
+    /// are configured
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub sit: Option<Duration>,
+
+    /// Time spent standing before the reminder to sit, such as 15m or 900s, rejected when stances
+    /// are configured
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub stand: Option<Duration>,
+
+    /// Name of the stance the timer starts in
+    #[arg(long, value_name = "STANCE")]
+    pub start: Option<String>,
+
+    /// Read the settings from this file instead of $XDG_CONFIG_HOME/get-up/config.toml
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the sitting and standing totals per day and per week from the session history
+    Report(ReportArgs),
+    /// Run the timer in the background, controlled through a Unix socket
+    Daemon,
+    /// Send a command to the running daemon
+    Ctl(CtlArgs),
+    /// Print the state of the daemon for a status bar
+    Status(StatusArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    /// Keep printing a line every second, also while the daemon is not running
+    #[arg(long)]
+    pub follow: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    pub format: StatusFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct CtlArgs {
+    #[command(subcommand)]
+    pub command: CtlCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum CtlCommand {
+    /// Pause the timer
+    Pause,
+    /// Resume the timer
+    Resume,
+    /// Pause the timer when it runs, resume it otherwise
+    Toggle,
+    /// Skip to the next stance
+    Next,
+    /// Restart the timer of the current stance
+    Reset,
+    /// Snooze the last stance change
+    Snooze,
+    /// Confirm the stance change the timer is waiting for
+    Acknowledge,
+    /// Skip to the next phase of the Pomodoro cycle
+    SkipPhase,
+    /// Lengthen the duration of a stance by the step duration
+    Increase { stance: String },
+    /// Shorten the duration of a stance by the step duration
+    Decrease { stance: String },
+    /// Set the duration of a stance
+    SetDuration {
+        stance: String,
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+    /// Print the current stance and the time left
+    Status {
+        /// Print the full status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stop the daemon
+    Quit,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Number of days covered by the report
+    #[arg(long, value_enum, default_value_t)]
+    pub period: ReportPeriod,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ReportFormat,
+}
+
+impl Cli {
+    /// Overrides the values of `config` with the ones given on the command line.
+    pub fn apply(&self, config: &mut Config) -> Result<(), ConfigError> {
+        if !config.stances.is_empty() && (self.sit.is_some() || self.stand.is_some()) {
+            return Err(ConfigError::Invalid(
+                "--sit and --stand cannot be used with [[stances]], set the duration of each \
+                 stance in the configuration file instead"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(sit) = self.sit {
+            config.sitting_duration = sit;
+        }
+        if let Some(stand) = self.stand {
+            config.standing_duration = stand;
+        }
+        if let Some(start) = &self.start {
+            config.start_stance = start.clone();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_durations_and_start() {
+        let cli = Cli::try_parse_from([
+            "get-up", "--sit", "45m", "--stand", "15m", "--start", "standing",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.sit, Some(Duration::from_secs(2700)));
+        assert_eq!(cli.stand, Some(Duration::from_secs(900)));
+        assert_eq!(cli.start.as_deref(), Some("standing"));
+    }
+
+    #[test]
+    fn test_parse_invalid_duration() {
+        assert!(Cli::try_parse_from(["get-up", "--sit", "forever"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let cli = Cli::try_parse_from(["get-up", "report", "--period", "month", "--format", "csv"])
+            .unwrap();
+
+        let Some(Command::Report(args)) = cli.command else {
+            panic!("expected the report subcommand");
+        };
+        assert_eq!(args.period, ReportPeriod::Month);
+        assert_eq!(args.format, ReportFormat::Csv);
+    }
+
+    #[test]
+    fn test_parse_ctl() {
+        let cli =
+            Cli::try_parse_from(["get-up", "ctl", "set-duration", "standing", "20m"]).unwrap();
+
+        let Some(Command::Ctl(args)) = cli.command else {
+            panic!("expected the ctl subcommand");
+        };
+        assert_eq!(
+            args.command,
+            CtlCommand::SetDuration {
+                stance: "standing".to_string(),
+                duration: Duration::from_secs(1200)
+            }
+        );
+        assert!(Cli::try_parse_from(["get-up", "ctl", "increase"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let cli =
+            Cli::try_parse_from(["get-up", "status", "--follow", "--format", "waybar"]).unwrap();
+
+        let Some(Command::Status(args)) = cli.command else {
+            panic!("expected the status subcommand");
+        };
+        assert!(args.follow);
+        assert_eq!(args.format, StatusFormat::Waybar);
+    }
+
+    #[test]
+    fn test_apply_overrides_config() {
+        let cli = Cli::try_parse_from(["get-up", "--stand", "1h30m"]).unwrap();
+        let mut config = Config::default();
+
+        cli.apply(&mut config).unwrap();
+
+        assert_eq!(config.standing_duration, Duration::from_secs(5400));
+        assert_eq!(config.sitting_duration, Config::default().sitting_duration);
+    }
+
+    #[test]
+    fn test_apply_rejects_durations_with_stances() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[stances]]
+            name = "walking"
+            duration = "20m"
+            posture = "standing"
+            "#,
+        )
+        .unwrap();
+
+        let cli = Cli::try_parse_from(["get-up", "--sit", "45m"]).unwrap();
+        assert!(matches!(
+            cli.apply(&mut config),
+            Err(ConfigError::Invalid(_))
+        ));
+
+        let cli = Cli::try_parse_from(["get-up", "--start", "walking"]).unwrap();
+        cli.apply(&mut config).unwrap();
+        assert_eq!(config.start_stance, "walking");
+    }
+}
@@ -0,0 +1,673 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use toml_edit::{value, DocumentMut};
+
+use crate::{
+    calendar::CalendarAwareness,
+    desk::DeskControl,
+    duration::{self, format_duration},
+    escalation::Escalation,
+    hooks::Hooks,
+    idle::IdleDetection,
+    logind::LockDetection,
+    notification::NotifierKind,
+    paths,
+    pomodoro::Pomodoro,
+    ramp_up::RampUp,
+    schedule::Schedule,
+    stance::{self, Stance},
+};
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+pub const SITTING_DURATION_KEY: &str = "sitting_duration";
+pub const STANDING_DURATION_KEY: &str = "standing_duration";
+
+const DEFAULT_STEP_DURATION: Duration = Duration::from_secs(300);
+const DEFAULT_MIN_DURATION: Duration = Duration::from_secs(300);
+const DEFAULT_MAX_DURATION: Duration = Duration::from_secs(14400);
+const DEFAULT_SITTING_DURATION: Duration = Duration::from_secs(3600);
+const DEFAULT_STANDING_DURATION: Duration = Duration::from_secs(1800);
+const DEFAULT_SNOOZE_DURATION: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub sitting_duration: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub standing_duration: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub step_duration: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub min_duration: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub max_duration: Duration,
+    /// Name of the stance the timer starts in.
+    pub start_stance: String,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub snooze_duration: Duration,
+    pub require_acknowledgement: bool,
+    /// Standing time to reach every day, without a goal when unset.
+    #[serde(deserialize_with = "duration::deserialize_human_option")]
+    pub standing_goal: Option<Duration>,
+    pub ramp_up: RampUp,
+    pub pomodoro: Pomodoro,
+    pub escalation: Escalation,
+    pub notifiers: Vec<NotifierKind>,
+    pub notify_command: Option<String>,
+    pub idle: IdleDetection,
+    pub lock: LockDetection,
+    pub schedule: Schedule,
+    pub calendar: CalendarAwareness,
+    /// Stances replacing sitting and standing, along with their durations.
+    pub stances: Vec<Stance>,
+    /// Names of the stances in the order they follow each other, every stance once by default.
+    pub cycle: Vec<String>,
+    pub hooks: Hooks,
+    pub desk: DeskControl,
+    /// Registers the `org.getup.Timer` service on the session bus.
+    pub dbus_service: bool,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Edit(PathBuf, toml_edit::TomlError),
+    Write(PathBuf, io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => {
+                write!(f, "could not read config file {}: {}", path.display(), err)
+            }
+            ConfigError::Parse(path, err) => {
+                write!(f, "could not parse config file {}: {}", path.display(), err)
+            }
+            ConfigError::Edit(path, err) => {
+                write!(
+                    f,
+                    "could not update config file {}: {}",
+                    path.display(),
+                    err
+                )
+            }
+            ConfigError::Write(path, err) => {
+                write!(f, "could not write config file {}: {}", path.display(), err)
+            }
+            ConfigError::Invalid(reason) => write!(f, "invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sitting_duration: DEFAULT_SITTING_DURATION,
+            standing_duration: DEFAULT_STANDING_DURATION,
+            step_duration: DEFAULT_STEP_DURATION,
+            min_duration: DEFAULT_MIN_DURATION,
+            max_duration: DEFAULT_MAX_DURATION,
+            start_stance: stance::SITTING.to_string(),
+            snooze_duration: DEFAULT_SNOOZE_DURATION,
+            require_acknowledgement: false,
+            standing_goal: None,
+            ramp_up: RampUp::default(),
+            pomodoro: Pomodoro::default(),
+            escalation: Escalation::default(),
+            notifiers: vec![NotifierKind::Desktop],
+            notify_command: None,
+            idle: IdleDetection::default(),
+            lock: LockDetection::default(),
+            schedule: Schedule::default(),
+            calendar: CalendarAwareness::default(),
+            stances: Vec::new(),
+            cycle: Vec::new(),
+            hooks: Hooks::default(),
+            desk: DeskControl::default(),
+            dbus_service: false,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration at `path`, using the defaults when the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(ConfigError::Read(path.to_path_buf(), err)),
+        };
+
+        let config: Config =
+            toml::from_str(&contents).map_err(|err| ConfigError::Parse(path.to_path_buf(), err))?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.step_duration.is_zero() {
+            return Err(ConfigError::Invalid(
+                "step_duration must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.snooze_duration.is_zero() {
+            return Err(ConfigError::Invalid(
+                "snooze_duration must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.standing_goal.is_some_and(|goal| goal.is_zero()) {
+            return Err(ConfigError::Invalid(
+                "standing_goal must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.escalation.interval.is_zero() {
+            return Err(ConfigError::Invalid(
+                "escalation.interval must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.idle.threshold.is_zero() {
+            return Err(ConfigError::Invalid(
+                "idle.threshold must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.hooks.timeout.is_zero() {
+            return Err(ConfigError::Invalid(
+                "hooks.timeout must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.schedule.enabled {
+            self.schedule
+                .validate()
+                .map_err(|reason| ConfigError::Invalid(format!("schedule: {}", reason)))?;
+        }
+
+        if self.pomodoro.enabled {
+            self.pomodoro
+                .validate()
+                .map_err(|reason| ConfigError::Invalid(format!("pomodoro: {}", reason)))?;
+        }
+
+        if self.desk.enabled {
+            self.desk
+                .validate()
+                .map_err(|reason| ConfigError::Invalid(format!("desk: {}", reason)))?;
+        }
+
+        if self.min_duration.is_zero() {
+            return Err(ConfigError::Invalid(
+                "min_duration must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.min_duration > self.max_duration {
+            return Err(ConfigError::Invalid(format!(
+                "min_duration ({}) must not be greater than max_duration ({})",
+                format_duration(self.min_duration),
+                format_duration(self.max_duration)
+            )));
+        }
+
+        // The configured stances replace the sitting and standing durations
+        let durations = if self.stances.is_empty() {
+            vec![
+                (SITTING_DURATION_KEY.to_string(), self.sitting_duration),
+                (STANDING_DURATION_KEY.to_string(), self.standing_duration),
+            ]
+        } else {
+            self.stances
+                .iter()
+                .map(|stance| (format!("the duration of {}", stance.name), stance.duration))
+                .collect()
+        };
+
+        for (key, duration) in durations {
+            if duration < self.min_duration || duration > self.max_duration {
+                return Err(ConfigError::Invalid(format!(
+                    "{} ({}) must be between min_duration ({}) and max_duration ({})",
+                    key,
+                    format_duration(duration),
+                    format_duration(self.min_duration),
+                    format_duration(self.max_duration)
+                )));
+            }
+        }
+
+        self.validate_stances()?;
+
+        if self.ramp_up.enabled {
+            self.ramp_up
+                .validate(self.min_duration, self.max_duration)
+                .map_err(|reason| ConfigError::Invalid(format!("ramp_up: {}", reason)))?;
+
+            if !self
+                .stances()
+                .iter()
+                .any(|stance| stance.name == self.ramp_up.stance)
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "ramp_up: unknown stance \"{}\"",
+                    self.ramp_up.stance
+                )));
+            }
+        }
+
+        if self.notifiers.contains(&NotifierKind::Command)
+            && self
+                .notify_command
+                .as_deref()
+                .unwrap_or_default()
+                .trim()
+                .is_empty()
+        {
+            return Err(ConfigError::Invalid(
+                "notify_command must be set to use the command notifier".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_stances(&self) -> Result<(), ConfigError> {
+        let stances = self.stances();
+
+        for (index, stance) in stances.iter().enumerate() {
+            if stance.name.trim().is_empty() {
+                return Err(ConfigError::Invalid(
+                    "the name of a stance must not be empty".to_string(),
+                ));
+            }
+
+            if stances[..index]
+                .iter()
+                .any(|other| other.name == stance.name)
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "the stance {} is defined twice",
+                    stance.name
+                )));
+            }
+        }
+
+        for name in self.cycle.iter().chain([&self.start_stance]) {
+            if !stances.iter().any(|stance| &stance.name == name) {
+                return Err(ConfigError::Invalid(format!(
+                    "unknown stance \"{}\", expected one of {}",
+                    name,
+                    stances
+                        .iter()
+                        .map(|stance| stance.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+
+        if self.cycle().len() < 2 {
+            return Err(ConfigError::Invalid(
+                "the cycle must contain at least two stances".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The configured stances, sitting and standing when there are none.
+    pub fn stances(&self) -> Vec<Stance> {
+        if self.stances.is_empty() {
+            return Stance::defaults(self.sitting_duration, self.standing_duration);
+        }
+
+        self.stances.clone()
+    }
+
+    /// Indices in `stances` of the stances of the cycle, in order.
+    pub fn cycle(&self) -> Vec<usize> {
+        let stances = self.stances();
+
+        if self.cycle.is_empty() {
+            return (0..stances.len()).collect();
+        }
+
+        self.cycle
+            .iter()
+            .filter_map(|name| stances.iter().position(|stance| &stance.name == name))
+            .collect()
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+/// Writes the given duration settings to the file at `path`, creating it if needed and
+/// leaving the rest of its content, comments included, untouched.
+pub fn write_durations(path: &Path, durations: &[(&str, Duration)]) -> Result<(), ConfigError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(ConfigError::Read(path.to_path_buf(), err)),
+    };
+
+    let mut document: DocumentMut = contents
+        .parse()
+        .map_err(|err| ConfigError::Edit(path.to_path_buf(), err))?;
+
+    for (key, duration) in durations {
+        document[key] = value(format_duration(*duration));
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| ConfigError::Write(path.to_path_buf(), err))?;
+    }
+
+    fs::write(path, document.to_string()).map_err(|err| ConfigError::Write(path.to_path_buf(), err))
+}
+
+/// Writes the durations of the `[[stances]]` at the given indices to the file at `path`, leaving
+/// the rest of its content untouched.
+pub fn write_stance_durations(
+    path: &Path,
+    durations: &[(usize, Duration)],
+) -> Result<(), ConfigError> {
+    let contents =
+        fs::read_to_string(path).map_err(|err| ConfigError::Read(path.to_path_buf(), err))?;
+
+    let mut document: DocumentMut = contents
+        .parse()
+        .map_err(|err| ConfigError::Edit(path.to_path_buf(), err))?;
+
+    let Some(stances) = document
+        .get_mut("stances")
+        .and_then(|stances| stances.as_array_of_tables_mut())
+    else {
+        return Err(ConfigError::Invalid(format!(
+            "{} does not define any stance",
+            path.display()
+        )));
+    };
+
+    for (index, duration) in durations {
+        if let Some(stance) = stances.get_mut(*index) {
+            stance["duration"] = value(format_duration(*duration));
+        }
+    }
+
+    fs::write(path, document.to_string()).map_err(|err| ConfigError::Write(path.to_path_buf(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_dir::TestDir, State};
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            sitting_duration = "45m"
+            standing_duration = "15m"
+            step_duration = "1m"
+            min_duration = "1m"
+            max_duration = "2h"
+            start_stance = "standing"
+            standing_goal = "2h"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.sitting_duration, Duration::from_secs(2700));
+        assert_eq!(config.standing_duration, Duration::from_secs(900));
+        assert_eq!(config.step_duration, Duration::from_secs(60));
+        assert_eq!(config.min_duration, Duration::from_secs(60));
+        assert_eq!(config.max_duration, Duration::from_secs(7200));
+        assert_eq!(config.start_stance, "standing");
+        assert_eq!(config.standing_goal, Some(Duration::from_secs(7200)));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_config_partial_uses_defaults() {
+        let config: Config = toml::from_str("standing_duration = \"20m\"").unwrap();
+
+        assert_eq!(config.sitting_duration, DEFAULT_SITTING_DURATION);
+        assert_eq!(config.standing_duration, Duration::from_secs(1200));
+        assert_eq!(config.standing_goal, None);
+    }
+
+    #[test]
+    fn test_parse_config_invalid_duration() {
+        assert!(toml::from_str::<Config>("sitting_duration = \"soon\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_config_unknown_key() {
+        assert!(toml::from_str::<Config>("sitting = \"45m\"").is_err());
+    }
+
+    #[test]
+    fn test_validate_duration_out_of_bounds() {
+        let config = Config {
+            sitting_duration: Duration::from_secs(18000),
+            ..Config::default()
+        };
+
+        assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_validate_min_greater_than_max() {
+        let config = Config {
+            min_duration: Duration::from_secs(7200),
+            max_duration: Duration::from_secs(3600),
+            ..Config::default()
+        };
+
+        assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_parse_stances_and_cycle() {
+        let config: Config = toml::from_str(
+            r#"
+            cycle = ["sitting", "standing", "walking", "standing"]
+
+            [[stances]]
+            name = "sitting"
+            posture = "sitting"
+            duration = "45m"
+
+            [[stances]]
+            name = "standing"
+            posture = "standing"
+            duration = "15m"
+
+            [[stances]]
+            name = "walking"
+            posture = "standing"
+            duration = "20m"
+            message = "Walk"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.stances()[2].name, "walking");
+        assert_eq!(config.cycle(), vec![0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_default_stances() {
+        let config = Config::default();
+
+        assert_eq!(
+            config.stances(),
+            Stance::defaults(DEFAULT_SITTING_DURATION, DEFAULT_STANDING_DURATION)
+        );
+        assert_eq!(config.cycle(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_validate_cycle() {
+        let unknown = Config {
+            cycle: vec!["sitting".to_string(), "lying".to_string()],
+            ..Config::default()
+        };
+        let single = Config {
+            cycle: vec!["sitting".to_string()],
+            ..Config::default()
+        };
+        let duplicate = Config {
+            stances: vec![
+                Stance::new("sitting", State::Sitting, DEFAULT_SITTING_DURATION),
+                Stance::new("sitting", State::Standing, DEFAULT_STANDING_DURATION),
+            ],
+            ..Config::default()
+        };
+
+        for config in [unknown, single, duplicate] {
+            assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+        }
+    }
+
+    #[test]
+    fn test_validate_stances_ignores_sitting_and_standing_durations() {
+        let config = Config {
+            min_duration: Duration::from_secs(3600),
+            stances: vec![
+                Stance::new("sitting", State::Sitting, Duration::from_secs(5400)),
+                Stance::new("standing", State::Standing, Duration::from_secs(3600)),
+            ],
+            ..Config::default()
+        };
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_ramp_up() {
+        let config: Config = toml::from_str(
+            r#"
+            [ramp_up]
+            enabled = true
+            start = "10m"
+            step = "5m"
+            every = "week"
+            target = "45m"
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let unknown = Config {
+            ramp_up: RampUp {
+                stance: "walking".to_string(),
+                ..config.ramp_up.clone()
+            },
+            ..config.clone()
+        };
+        let decreasing = Config {
+            ramp_up: RampUp {
+                start: Duration::from_secs(3600),
+                ..config.ramp_up.clone()
+            },
+            ..config
+        };
+
+        for config in [unknown, decreasing] {
+            assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+        }
+    }
+
+    #[test]
+    fn test_parse_notifiers() {
+        let config: Config = toml::from_str(
+            r#"
+            notifiers = ["desktop", "bell", "command"]
+            notify_command = "notify-send \"$GET_UP_TITLE\""
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.notifiers,
+            vec![
+                NotifierKind::Desktop,
+                NotifierKind::Bell,
+                NotifierKind::Command
+            ]
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_command_notifier_without_command() {
+        let config = Config {
+            notifiers: vec![NotifierKind::Command],
+            ..Config::default()
+        };
+
+        assert!(matches!(config.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_load_missing_file_uses_defaults() {
+        let dir = TestDir::new("missing");
+        let path = dir.join(CONFIG_FILE_NAME);
+
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_write_durations_preserves_other_content() {
+        let dir = TestDir::new("write");
+        let path = dir.join(CONFIG_FILE_NAME);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# my settings\nstep_duration = \"1m\"\n").unwrap();
+
+        write_durations(&path, &[(SITTING_DURATION_KEY, Duration::from_secs(2700))]).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("# my settings"));
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.sitting_duration, Duration::from_secs(2700));
+        assert_eq!(config.step_duration, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_write_stance_durations() {
+        let dir = TestDir::new("write-stances");
+        let path = dir.join(CONFIG_FILE_NAME);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "[[stances]]\nname = \"sitting\"\nposture = \"sitting\"\nduration = \"1h\"\n\n[[stances]]\nname = \"walking\"\nposture = \"standing\"\nduration = \"10m\"\n",
+        )
+        .unwrap();
+
+        write_stance_durations(&path, &[(1, Duration::from_secs(900))]).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.stances[0].duration, Duration::from_secs(3600));
+        assert_eq!(config.stances[1].duration, Duration::from_secs(900));
+    }
+}
@@ -0,0 +1,146 @@
+use std::error::Error;
+
+use crate::{
+    cli::{CtlArgs, CtlCommand},
+    daemon::{self, Client, Reply, Request, Status},
+    format_duration_hours_minutes_seconds, stance,
+};
+
+pub fn run(args: &CtlArgs) -> Result<(), Box<dyn Error>> {
+    let path =
+        daemon::socket_path().ok_or("could not locate the control socket, HOME is not set")?;
+    let mut client = Client::connect(&path).map_err(|err| {
+        format!(
+            "could not reach the daemon on {} ({}), is `get-up daemon` running?",
+            path.display(),
+            err
+        )
+    })?;
+
+    let status = match client.send(&request(&args.command))? {
+        Reply::Status(status) => status,
+        Reply::Error(error) => return Err(error.into()),
+    };
+
+    if let CtlCommand::Status { json } = args.command {
+        if json {
+            println!("{}", serde_json::to_string(&status)?);
+        } else {
+            println!("{}", describe(&status));
+        }
+    }
+
+    Ok(())
+}
+
+fn request(command: &CtlCommand) -> Request {
+    match command {
+        CtlCommand::Pause => Request::Pause,
+        CtlCommand::Resume => Request::Resume,
+        CtlCommand::Toggle => Request::Toggle,
+        CtlCommand::Next => Request::Next,
+        CtlCommand::Reset => Request::Reset,
+        CtlCommand::Snooze => Request::Snooze,
+        CtlCommand::Acknowledge => Request::Acknowledge,
+        CtlCommand::SkipPhase => Request::SkipPhase,
+        CtlCommand::Increase { stance } => Request::Increase(stance.clone()),
+        CtlCommand::Decrease { stance } => Request::Decrease(stance.clone()),
+        CtlCommand::SetDuration { stance, duration } => {
+            Request::SetDuration(stance.clone(), *duration)
+        }
+        CtlCommand::Status { .. } => Request::Status,
+        CtlCommand::Quit => Request::Quit,
+    }
+}
+
+/// One line summary of the status, such as "Standing, 0h12m30s left [PAUSED]".
+pub fn describe(status: &Status) -> String {
+    let summary = match &status.awaiting {
+        Some(stance) => format!("Waiting for you to switch to {}", stance),
+        None => format!(
+            "{}{}, {} left",
+            stance::label(&status.stance),
+            if status.snoozed { " (snoozed)" } else { "" },
+            format_duration_hours_minutes_seconds(status.time_left())
+        ),
+    };
+
+    let label = if status.paused {
+        Some("[PAUSED]")
+    } else {
+        status.holds.first().map(|hold| hold.label())
+    };
+
+    match label {
+        Some(label) => format!("{} {}", summary, label),
+        None => summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Local;
+
+    use super::*;
+    use crate::{Hold, State};
+
+    fn status() -> Status {
+        Status {
+            stance: "standing".to_string(),
+            posture: State::Standing,
+            paused: false,
+            holds: Vec::new(),
+            awaiting: None,
+            snoozed: false,
+            elapsed_seconds: 1050,
+            duration_seconds: 1800,
+            durations_seconds: BTreeMap::from([
+                ("sitting".to_string(), 3600),
+                ("standing".to_string(), 1800),
+            ]),
+            period_start: Local::now(),
+            period_paused_seconds: 0,
+            warning: None,
+            pomodoro: None,
+            ramp_up: None,
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&status()), "Standing, 0h12m30s left");
+    }
+
+    #[test]
+    fn test_describe_paused_and_held() {
+        let mut status = status();
+        status.holds = vec![Hold::Idle];
+        assert_eq!(describe(&status), "Standing, 0h12m30s left [IDLE]");
+
+        status.paused = true;
+        assert_eq!(describe(&status), "Standing, 0h12m30s left [PAUSED]");
+    }
+
+    #[test]
+    fn test_describe_awaiting() {
+        let status = Status {
+            awaiting: Some("sitting".to_string()),
+            ..status()
+        };
+
+        assert_eq!(describe(&status), "Waiting for you to switch to sitting");
+    }
+
+    #[test]
+    fn test_request() {
+        assert_eq!(request(&CtlCommand::Status { json: true }), Request::Status);
+        assert_eq!(
+            request(&CtlCommand::Increase {
+                stance: "walking".to_string()
+            }),
+            Request::Increase("walking".to_string())
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt, fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration::{format_duration, parse_duration},
+    handle_async, paths,
+    pomodoro::Session,
+    publish,
+    ramp_up::Progress,
+    update, Hold, Message, Model, RunningState, State, POLL_DURATION,
+};
+
+pub const SOCKET_FILE_NAME: &str = "daemon.sock";
+
+/// How long the daemon waits for the replies still being written when quitting.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A command sent to the daemon, one per line on the control socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Pause,
+    Resume,
+    Toggle,
+    Next,
+    Reset,
+    Snooze,
+    Acknowledge,
+    SkipPhase,
+    Status,
+    Increase(String),
+    Decrease(String),
+    SetDuration(String, Duration),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRequestError(String);
+
+impl fmt::Display for ParseRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRequestError {}
+
+impl FromStr for Request {
+    type Err = ParseRequestError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let request = match words.as_slice() {
+            ["pause"] => Request::Pause,
+            ["resume"] => Request::Resume,
+            ["toggle"] => Request::Toggle,
+            ["next"] => Request::Next,
+            ["reset"] => Request::Reset,
+            ["snooze"] => Request::Snooze,
+            ["acknowledge"] => Request::Acknowledge,
+            ["skip-phase"] => Request::SkipPhase,
+            ["status"] => Request::Status,
+            ["quit"] => Request::Quit,
+            ["increase", stance] => Request::Increase(stance.to_string()),
+            ["decrease", stance] => Request::Decrease(stance.to_string()),
+            ["set-duration", stance, duration] => {
+                let duration =
+                    parse_duration(duration).map_err(|err| ParseRequestError(err.to_string()))?;
+
+                Request::SetDuration(stance.to_string(), duration)
+            }
+            [command @ ("increase" | "decrease"), ..] => {
+                return Err(ParseRequestError(format!("usage: {} <STANCE>", command)))
+            }
+            ["set-duration", ..] => {
+                return Err(ParseRequestError(
+                    "usage: set-duration <STANCE> <DURATION>".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ParseRequestError(format!(
+                    "unknown command \"{}\"",
+                    line.trim()
+                )))
+            }
+        };
+
+        Ok(request)
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Request::Pause => write!(f, "pause"),
+            Request::Resume => write!(f, "resume"),
+            Request::Toggle => write!(f, "toggle"),
+            Request::Next => write!(f, "next"),
+            Request::Reset => write!(f, "reset"),
+            Request::Snooze => write!(f, "snooze"),
+            Request::Acknowledge => write!(f, "acknowledge"),
+            Request::SkipPhase => write!(f, "skip-phase"),
+            Request::Status => write!(f, "status"),
+            Request::Quit => write!(f, "quit"),
+            Request::Increase(stance) => write!(f, "increase {}", stance),
+            Request::Decrease(stance) => write!(f, "decrease {}", stance),
+            Request::SetDuration(stance, duration) => {
+                write!(f, "set-duration {} {}", stance, format_duration(*duration))
+            }
+        }
+    }
+}
+
+/// State of the daemon sent back after every request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub stance: String,
+    pub posture: State,
+    /// Paused by the user, holds being listed separately.
+    pub paused: bool,
+    pub holds: Vec<Hold>,
+    /// Stance waiting for the user to confirm the change to it.
+    pub awaiting: Option<String>,
+    pub snoozed: bool,
+    pub elapsed_seconds: u64,
+    pub duration_seconds: u64,
+    pub durations_seconds: BTreeMap<String, u64>,
+    pub period_start: DateTime<Local>,
+    pub period_paused_seconds: u64,
+    /// Last error reported by the daemon, such as a failed notification.
+    pub warning: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pomodoro: Option<PomodoroStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ramp_up: Option<Progress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomodoroStatus {
+    pub session: Session,
+    pub elapsed_seconds: u64,
+}
+
+impl Status {
+    pub fn time_left(&self) -> Duration {
+        Duration::from_secs(self.duration_seconds.saturating_sub(self.elapsed_seconds))
+    }
+}
+
+/// Answer to a request, one JSON object per line on the control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reply {
+    Status(Status),
+    Error(String),
+}
+
+pub fn socket_path() -> Option<PathBuf> {
+    paths::runtime_dir().map(|dir| dir.join(SOCKET_FILE_NAME))
+}
+
+/// Runs the timer without a terminal until a `quit` request, serving the control socket.
+pub fn run(model: &mut Model) -> Result<(), Box<dyn Error>> {
+    let path = socket_path().ok_or("could not locate the control socket, HOME is not set")?;
+    let server = listen(&path, model.sender.clone())?;
+    eprintln!("get-up: listening on {}", path.display());
+
+    let mut reported = None;
+    while model.running_state != RunningState::Done {
+        let mut current_message =
+            handle_async(model).or_else(|| model.inbox.recv_timeout(POLL_DURATION).ok());
+
+        while let Some(message) = current_message {
+            current_message = update(model, message);
+        }
+        publish(model);
+
+        if model.status != reported {
+            if let Some(warning) = &model.status {
+                eprintln!("get-up: {}", warning.text);
+            }
+            reported.clone_from(&model.status);
+        }
+    }
+
+    let _ = fs::remove_file(&path);
+    server.wait_for_replies(REPLY_TIMEOUT);
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct Server {
+    pending: Arc<AtomicUsize>,
+}
+
+impl Server {
+    /// Waits until every pending reply was written, such as the one answering `quit`.
+    pub fn wait_for_replies(&self, timeout: Duration) {
+        let start = Instant::now();
+
+        while self.pending.load(Ordering::SeqCst) > 0 && start.elapsed() < timeout {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+/// Serves `path` in the background, forwarding the requests as `Message::Control`.
+pub fn listen(path: &Path, sender: Sender<Message>) -> io::Result<Server> {
+    if UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("a daemon is already listening on {}", path.display()),
+        ));
+    }
+
+    // Only the user may control the timer
+    if let Some(parent) = path.parent() {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)?;
+    }
+    // Left behind by a daemon that did not quit cleanly
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    let pending = Arc::new(AtomicUsize::new(0));
+
+    let server = Server {
+        pending: pending.clone(),
+    };
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let sender = sender.clone();
+            let pending = pending.clone();
+            thread::spawn(move || serve(stream, sender, &pending));
+        }
+    });
+
+    Ok(server)
+}
+
+fn serve(stream: UnixStream, sender: Sender<Message>, pending: &AtomicUsize) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        pending.fetch_add(1, Ordering::SeqCst);
+        let answered = answer(&line, &sender, &mut writer);
+        pending.fetch_sub(1, Ordering::SeqCst);
+
+        if !answered? {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns false once the model is gone.
+fn answer(line: &str, sender: &Sender<Message>, writer: &mut UnixStream) -> io::Result<bool> {
+    let reply = match line.parse::<Request>() {
+        Ok(request) => {
+            let (reply_sender, reply) = mpsc::channel();
+            if sender
+                .send(Message::Control(request, reply_sender))
+                .is_err()
+            {
+                return Ok(false);
+            }
+            match reply.recv() {
+                Ok(reply) => reply,
+                Err(_) => return Ok(false),
+            }
+        }
+        Err(err) => Reply::Error(err.to_string()),
+    };
+
+    let mut json = serde_json::to_string(&reply)?;
+    json.push('\n');
+    writer.write_all(json.as_bytes())?;
+
+    Ok(true)
+}
+
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    pub fn connect(path: &Path) -> io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        Ok(Self { reader, writer })
+    }
+
+    pub fn send(&mut self, request: &Request) -> io::Result<Reply> {
+        writeln!(self.writer, "{}", request)?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the daemon closed the connection",
+            ));
+        }
+
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn status() -> Status {
+        Status {
+            stance: "standing".to_string(),
+            posture: State::Standing,
+            paused: false,
+            holds: vec![Hold::Idle],
+            awaiting: None,
+            snoozed: false,
+            elapsed_seconds: 60,
+            duration_seconds: 1800,
+            durations_seconds: BTreeMap::from([
+                ("sitting".to_string(), 3600),
+                ("standing".to_string(), 1800),
+            ]),
+            period_start: Local::now(),
+            period_paused_seconds: 0,
+            warning: None,
+            pomodoro: None,
+            ramp_up: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!("pause".parse(), Ok(Request::Pause));
+        assert_eq!(" next\n".parse(), Ok(Request::Next));
+        assert_eq!("skip-phase".parse(), Ok(Request::SkipPhase));
+        assert_eq!(
+            "set-duration standing 20m".parse(),
+            Ok(Request::SetDuration(
+                "standing".to_string(),
+                Duration::from_secs(1200)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_request() {
+        assert!("jump".parse::<Request>().is_err());
+        assert!("set-duration sitting".parse::<Request>().is_err());
+        assert!("set-duration sitting soon".parse::<Request>().is_err());
+    }
+
+    #[test]
+    fn test_parse_increase_and_decrease() {
+        assert_eq!(
+            "increase sitting".parse(),
+            Ok(Request::Increase("sitting".to_string()))
+        );
+        assert_eq!(
+            "decrease walking".parse(),
+            Ok(Request::Decrease("walking".to_string()))
+        );
+        assert!("increase".parse::<Request>().is_err());
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = Request::SetDuration("sitting".to_string(), Duration::from_secs(5400));
+
+        assert_eq!(request.to_string(), "set-duration sitting 1h30m");
+        assert_eq!(request.to_string().parse(), Ok(request));
+    }
+
+    #[test]
+    fn test_client_and_server() {
+        let dir = TestDir::new("socket");
+        let path = dir.join(SOCKET_FILE_NAME);
+        let (sender, inbox) = mpsc::channel();
+        listen(&path, sender).unwrap();
+
+        thread::spawn(move || {
+            while let Ok(Message::Control(request, reply)) = inbox.recv() {
+                let mut status = status();
+                status.paused = request == Request::Pause;
+                let _ = reply.send(Reply::Status(status));
+            }
+        });
+
+        let mut client = Client::connect(&path).unwrap();
+        let Reply::Status(status) = client.send(&Request::Pause).unwrap() else {
+            panic!("expected a status");
+        };
+        assert!(status.paused);
+        assert_eq!(status.holds, vec![Hold::Idle]);
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"jump\n").unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        assert_eq!(line, "{\"error\":\"unknown command \\\"jump\\\"\"}\n");
+
+        assert!(listen(&path, mpsc::channel().0).is_err());
+    }
+
+    #[test]
+    fn test_listen_restricts_permissions() {
+        let dir = TestDir::new("socket-permissions");
+        let path = dir.join("runtime").join(SOCKET_FILE_NAME);
+
+        listen(&path, mpsc::channel().0).unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+        assert_eq!(mode(&path), 0o600);
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use zbus::{
+    blocking::{object_server::InterfaceRef, Connection},
+    fdo::{self, RequestNameFlags, RequestNameReply},
+    interface,
+    object_server::SignalContext,
+};
+
+use crate::{
+    daemon::{Reply, Request, Status},
+    Message,
+};
+
+pub const NAME: &str = "org.getup.Timer";
+pub const PATH: &str = "/org/getup/Timer";
+
+/// How long a method call waits for the timer to apply it.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct TimerInterface {
+    sender: Sender<Message>,
+    status: Arc<Mutex<Status>>,
+}
+
+impl TimerInterface {
+    fn send(&self, request: Request) -> fdo::Result<()> {
+        let (reply_sender, reply) = mpsc::channel();
+
+        self.sender
+            .send(Message::Control(request, reply_sender))
+            .map_err(|_| fdo::Error::Failed("the timer has stopped".to_string()))?;
+
+        match reply.recv_timeout(REPLY_TIMEOUT) {
+            Ok(Reply::Status(_)) => Ok(()),
+            Ok(Reply::Error(error)) => Err(fdo::Error::Failed(error)),
+            Err(_) => Err(fdo::Error::Failed(
+                "the timer did not answer in time".to_string(),
+            )),
+        }
+    }
+
+    fn status(&self) -> Status {
+        self.status
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[interface(name = "org.getup.Timer")]
+impl TimerInterface {
+    fn pause(&self) -> fdo::Result<()> {
+        self.send(Request::Pause)
+    }
+
+    fn resume(&self) -> fdo::Result<()> {
+        self.send(Request::Resume)
+    }
+
+    fn toggle(&self) -> fdo::Result<()> {
+        self.send(Request::Toggle)
+    }
+
+    fn next(&self) -> fdo::Result<()> {
+        self.send(Request::Next)
+    }
+
+    fn reset(&self) -> fdo::Result<()> {
+        self.send(Request::Reset)
+    }
+
+    fn snooze(&self) -> fdo::Result<()> {
+        self.send(Request::Snooze)
+    }
+
+    fn acknowledge(&self) -> fdo::Result<()> {
+        self.send(Request::Acknowledge)
+    }
+
+    fn increase(&self, stance: &str) -> fdo::Result<()> {
+        self.send(Request::Increase(stance.to_string()))
+    }
+
+    fn decrease(&self, stance: &str) -> fdo::Result<()> {
+        self.send(Request::Decrease(stance.to_string()))
+    }
+
+    fn set_duration(&self, stance: &str, seconds: u64) -> fdo::Result<()> {
+        self.send(Request::SetDuration(
+            stance.to_string(),
+            Duration::from_secs(seconds),
+        ))
+    }
+
+    fn quit(&self) -> fdo::Result<()> {
+        self.send(Request::Quit)
+    }
+
+    #[zbus(property)]
+    fn stance(&self) -> String {
+        self.status().stance
+    }
+
+    /// Changes every second, so clients poll it instead of waiting for `PropertiesChanged`.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn remaining_seconds(&self) -> u64 {
+        self.status().time_left().as_secs()
+    }
+
+    #[zbus(property)]
+    fn paused(&self) -> bool {
+        is_paused(&self.status())
+    }
+
+    #[zbus(signal, name = "StanceChanged")]
+    async fn stance_switched(
+        ctxt: &SignalContext<'_>,
+        stance: &str,
+        duration_seconds: u64,
+    ) -> zbus::Result<()>;
+}
+
+fn is_paused(status: &Status) -> bool {
+    status.paused || !status.holds.is_empty()
+}
+
+/// Handle on the `org.getup.Timer` service, kept in sync with the model by `publish`.
+#[derive(Debug)]
+pub struct Service {
+    connection: Connection,
+    status: Arc<Mutex<Status>>,
+}
+
+impl Service {
+    pub fn start(sender: Sender<Message>, status: Status) -> zbus::Result<Self> {
+        Self::serve(Connection::session()?, sender, status)
+    }
+
+    /// Fails when another timer already owns the name.
+    pub fn serve(
+        connection: Connection,
+        sender: Sender<Message>,
+        status: Status,
+    ) -> zbus::Result<Self> {
+        let status = Arc::new(Mutex::new(status));
+        let timer = TimerInterface {
+            sender,
+            status: status.clone(),
+        };
+
+        connection.object_server().at(PATH, timer)?;
+        match connection.request_name_with_flags(NAME, RequestNameFlags::DoNotQueue.into())? {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {}
+            _ => return Err(zbus::Error::NameTaken),
+        }
+
+        Ok(Self { connection, status })
+    }
+
+    /// Updates the properties, signalling the ones that changed.
+    pub fn publish(&self, status: Status) -> zbus::Result<()> {
+        let previous = std::mem::replace(
+            &mut *self.status.lock().unwrap_or_else(PoisonError::into_inner),
+            status.clone(),
+        );
+        let stance_changed = previous.stance != status.stance;
+        let paused_changed = is_paused(&previous) != is_paused(&status);
+
+        if !stance_changed && !paused_changed {
+            return Ok(());
+        }
+
+        let timer = self.interface()?;
+        let ctxt = timer.signal_context();
+        let timer = timer.get();
+        zbus::block_on(async {
+            if stance_changed {
+                timer.stance_changed(ctxt).await?;
+            }
+            if paused_changed {
+                timer.paused_changed(ctxt).await?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub fn stance_changed(&self, stance: &str, duration: Duration) -> zbus::Result<()> {
+        let timer = self.interface()?;
+
+        zbus::block_on(TimerInterface::stance_switched(
+            timer.signal_context(),
+            stance,
+            duration.as_secs(),
+        ))
+    }
+
+    fn interface(&self) -> zbus::Result<InterfaceRef<TimerInterface>> {
+        self.connection
+            .object_server()
+            .interface::<_, TimerInterface>(PATH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use zbus::{
+        blocking::{fdo::PropertiesProxy, Proxy, ProxyBuilder},
+        CacheProperties,
+    };
+
+    use super::*;
+    use crate::{handle_async, publish, status, test_bus::TestBus, update, Model, RunningState};
+
+    const INTERFACE: &str = "org.getup.Timer";
+
+    fn spawn_timer(connection: Connection) -> thread::JoinHandle<()> {
+        let (ready, started) = mpsc::channel();
+
+        let timer = thread::spawn(move || {
+            let mut model = Model {
+                notifiers: Vec::new(),
+                ..Model::default()
+            };
+            model.bus =
+                Some(Service::serve(connection, model.sender.clone(), status(&model)).unwrap());
+            ready.send(()).unwrap();
+
+            while model.running_state != RunningState::Done {
+                let mut current_message = handle_async(&model)
+                    .or_else(|| model.inbox.recv_timeout(Duration::from_millis(10)).ok());
+                while let Some(message) = current_message {
+                    current_message = update(&mut model, message);
+                }
+                publish(&mut model);
+            }
+        });
+        started.recv().unwrap();
+
+        timer
+    }
+
+    fn proxy(connection: &Connection) -> Proxy<'static> {
+        ProxyBuilder::new(connection)
+            .destination(NAME)
+            .unwrap()
+            .path(PATH)
+            .unwrap()
+            .interface(INTERFACE)
+            .unwrap()
+            .cache_properties(CacheProperties::No)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_service_next() {
+        let bus = TestBus::start();
+        let timer = spawn_timer(bus.connect());
+
+        let connection = bus.connect();
+        let proxy = proxy(&connection);
+        let mut signals = proxy.receive_signal("StanceChanged").unwrap();
+        let properties = PropertiesProxy::builder(&connection)
+            .destination(NAME)
+            .unwrap()
+            .path(PATH)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut changes = properties.receive_properties_changed().unwrap();
+
+        assert_eq!(proxy.get_property::<String>("Stance").unwrap(), "sitting");
+        assert!(!proxy.get_property::<bool>("Paused").unwrap());
+
+        proxy.call_method("Next", &()).unwrap();
+
+        let signal = signals.next().unwrap();
+        assert_eq!(
+            signal.body().deserialize::<(String, u64)>().unwrap(),
+            ("standing".to_string(), 1800)
+        );
+        let change = changes.next().unwrap();
+        let args = change.args().unwrap();
+        assert_eq!(args.interface_name().as_str(), INTERFACE);
+        assert_eq!(
+            args.changed_properties().get("Stance").unwrap(),
+            &zbus::zvariant::Value::from("standing")
+        );
+
+        proxy.call_method("Pause", &()).unwrap();
+        assert_eq!(proxy.get_property::<String>("Stance").unwrap(), "standing");
+        assert!(proxy.get_property::<bool>("Paused").unwrap());
+        assert!(proxy.get_property::<u64>("RemainingSeconds").unwrap() > 1790);
+
+        proxy.call_method("Quit", &()).unwrap();
+        timer.join().unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_service_rejects_invalid_arguments() {
+        let bus = TestBus::start();
+        let timer = spawn_timer(bus.connect());
+
+        let connection = bus.connect();
+        let proxy = proxy(&connection);
+
+        assert!(proxy.call_method("Increase", &("lying",)).is_err());
+        assert!(proxy
+            .call_method("SetDuration", &("standing", 60 * 60 * 24u64))
+            .is_err());
+
+        proxy.call_method("Quit", &()).unwrap();
+        timer.join().unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_serve_name_taken() {
+        let bus = TestBus::start();
+        let _owner = bus.connect_as(NAME);
+        let (sender, _inbox) = mpsc::channel();
+
+        assert!(Service::serve(bus.connect(), sender, status(&Model::default())).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use nix::{
+    fcntl::{self, FcntlArg, OFlag},
+    libc,
+    sys::termios::{self, BaudRate, ControlFlags, SetArg},
+};
+use serde::Deserialize;
+
+use crate::{duration::format_duration, Message, State};
+
+const DEFAULT_DEVICE: &str = "/dev/ttyUSB0";
+const DEFAULT_BAUD_RATE: u32 = 9600;
+const PRESET_PLACEHOLDER: &str = "{preset}";
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Moves a motorized desk to the height of each stance when it changes.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeskControl {
+    pub enabled: bool,
+    pub device: PathBuf,
+    pub baud_rate: u32,
+    pub sit: Option<DeskCommand>,
+    pub stand: Option<DeskCommand>,
+    /// Bytes recalling a preset, `{preset}` standing for the number of the preset.
+    pub preset_command: Option<String>,
+}
+
+impl Default for DeskControl {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            device: PathBuf::from(DEFAULT_DEVICE),
+            baud_rate: DEFAULT_BAUD_RATE,
+            sit: None,
+            stand: None,
+            preset_command: None,
+        }
+    }
+}
+
+/// What is sent to the desk controller to reach the height of a stance.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum DeskCommand {
+    /// Number of a memory preset, recalled with `preset_command`
+    Preset(u8),
+    /// Bytes written in hexadecimal, such as `"F1 F1 05 00 05 7E"`
+    Bytes(String),
+}
+
+impl DeskControl {
+    /// Bytes moving the desk to the height of `stance`, if it should move.
+    pub fn sequence(&self, stance: State) -> Result<Option<Vec<u8>>, String> {
+        let command = match stance {
+            State::Sitting => &self.sit,
+            State::Standing => &self.stand,
+        };
+
+        match command {
+            None => Ok(None),
+            Some(DeskCommand::Bytes(bytes)) => parse_bytes(bytes, None).map(Some),
+            Some(DeskCommand::Preset(preset)) => {
+                let template = self
+                    .preset_command
+                    .as_deref()
+                    .ok_or("preset_command must be set to recall presets")?;
+
+                parse_bytes(template, Some(*preset)).map(Some)
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        baud_rate(self.baud_rate)?;
+
+        for stance in [State::Sitting, State::Standing] {
+            self.sequence(stance)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_bytes(text: &str, preset: Option<u8>) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+
+    for word in text.split_whitespace() {
+        if word == PRESET_PLACEHOLDER {
+            bytes.push(preset.ok_or("{preset} can only be used in preset_command")?);
+            continue;
+        }
+
+        if word.len() % 2 != 0 {
+            return Err(format!("invalid byte sequence \"{}\"", word));
+        }
+
+        for index in (0..word.len()).step_by(2) {
+            let byte = word
+                .get(index..index + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| format!("invalid byte sequence \"{}\"", word))?;
+            bytes.push(byte);
+        }
+    }
+
+    if bytes.is_empty() {
+        return Err("byte sequences must not be empty".to_string());
+    }
+
+    Ok(bytes)
+}
+
+fn baud_rate(rate: u32) -> Result<BaudRate, String> {
+    match rate {
+        1200 => Ok(BaudRate::B1200),
+        2400 => Ok(BaudRate::B2400),
+        4800 => Ok(BaudRate::B4800),
+        9600 => Ok(BaudRate::B9600),
+        19200 => Ok(BaudRate::B19200),
+        38400 => Ok(BaudRate::B38400),
+        57600 => Ok(BaudRate::B57600),
+        115200 => Ok(BaudRate::B115200),
+        _ => Err(format!("unsupported baud_rate {}", rate)),
+    }
+}
+
+#[derive(Debug)]
+pub enum DeskError {
+    Open(PathBuf, io::Error),
+    Configure(PathBuf, nix::Error),
+    Write(PathBuf, io::Error),
+    TimedOut(PathBuf, Duration),
+}
+
+impl fmt::Display for DeskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeskError::Open(path, err) => write!(f, "could not open {}: {}", path.display(), err),
+            DeskError::Configure(path, err) => {
+                write!(f, "could not configure {}: {}", path.display(), err)
+            }
+            DeskError::Write(path, err) => {
+                write!(f, "could not write to {}: {}", path.display(), err)
+            }
+            DeskError::TimedOut(path, timeout) => write!(
+                f,
+                "could not write to {} within {}",
+                path.display(),
+                format_duration(*timeout)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeskError {}
+
+pub trait DeskController: fmt::Debug {
+    /// Moves the desk in the background, reporting failures to the inbox.
+    fn move_to(&mut self, stance: State);
+}
+
+#[derive(Debug)]
+pub struct SerialDesk {
+    path: PathBuf,
+    port: Arc<File>,
+    sit: Option<Vec<u8>>,
+    stand: Option<Vec<u8>>,
+    sender: Sender<Message>,
+}
+
+impl SerialDesk {
+    /// Opens the port in raw mode, ignoring the modem control lines to never wait for a carrier.
+    pub fn open(config: &DeskControl, sender: Sender<Message>) -> Result<Self, DeskError> {
+        let path = config.device.clone();
+        let port = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(&path)
+            .map_err(|err| DeskError::Open(path.clone(), err))?;
+
+        let configure = || -> nix::Result<()> {
+            let mut settings = termios::tcgetattr(&port)?;
+            termios::cfmakeraw(&mut settings);
+            settings.control_flags.insert(ControlFlags::CLOCAL);
+            termios::cfsetspeed(
+                &mut settings,
+                baud_rate(config.baud_rate).unwrap_or(BaudRate::B9600),
+            )?;
+            termios::tcsetattr(&port, SetArg::TCSANOW, &settings)?;
+
+            // Writes happen on a worker thread given up on after a timeout, they can block
+            fcntl::fcntl(port.as_raw_fd(), FcntlArg::F_SETFL(OFlag::empty())).map(drop)
+        };
+        configure().map_err(|err| DeskError::Configure(path.clone(), err))?;
+
+        Ok(Self {
+            path,
+            port: Arc::new(port),
+            sit: config.sequence(State::Sitting).unwrap_or_default(),
+            stand: config.sequence(State::Standing).unwrap_or_default(),
+            sender,
+        })
+    }
+}
+
+impl DeskController for SerialDesk {
+    fn move_to(&mut self, stance: State) {
+        let sequence = match stance {
+            State::Sitting => &self.sit,
+            State::Standing => &self.stand,
+        };
+        let Some(sequence) = sequence.clone() else {
+            return;
+        };
+
+        let path = self.path.clone();
+        let port = Arc::clone(&self.port);
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            if let Err(err) = write(&path, port, sequence) {
+                let _ = sender.send(Message::Error(format!("Could not move the desk: {}", err)));
+            }
+        });
+    }
+}
+
+fn write(path: &Path, port: Arc<File>, sequence: Vec<u8>) -> Result<(), DeskError> {
+    let (done, result) = mpsc::channel();
+
+    // Left behind when it times out, until the port fails or the adapter comes back
+    thread::spawn(move || {
+        let written = (&*port)
+            .write_all(&sequence)
+            .and_then(|()| termios::tcdrain(&*port).map_err(io::Error::from));
+        let _ = done.send(written);
+    });
+
+    match result.recv_timeout(WRITE_TIMEOUT) {
+        Ok(written) => written.map_err(|err| DeskError::Write(path.to_path_buf(), err)),
+        Err(_) => Err(DeskError::TimedOut(path.to_path_buf(), WRITE_TIMEOUT)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use nix::{pty, unistd};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_desk_control() {
+        let desk: DeskControl = toml::from_str(
+            r#"
+            enabled = true
+            device = "/dev/ttyACM0"
+            baud_rate = 115200
+            sit = 1
+            stand = "F1F1 0600 067E"
+            preset_command = "F1 F1 {preset} 00 {preset} 7E"
+            "#,
+        )
+        .unwrap();
+
+        assert!(desk.validate().is_ok());
+        assert_eq!(
+            desk.sequence(State::Sitting).unwrap(),
+            Some(vec![0xF1, 0xF1, 0x01, 0x00, 0x01, 0x7E])
+        );
+        assert_eq!(
+            desk.sequence(State::Standing).unwrap(),
+            Some(vec![0xF1, 0xF1, 0x06, 0x00, 0x06, 0x7E])
+        );
+    }
+
+    #[test]
+    fn test_validate_desk_control() {
+        let invalid = [
+            DeskControl {
+                baud_rate: 1234,
+                ..DeskControl::default()
+            },
+            DeskControl {
+                sit: Some(DeskCommand::Bytes("F1 F".to_string())),
+                ..DeskControl::default()
+            },
+            DeskControl {
+                stand: Some(DeskCommand::Bytes("{preset}".to_string())),
+                ..DeskControl::default()
+            },
+            DeskControl {
+                stand: Some(DeskCommand::Preset(2)),
+                ..DeskControl::default()
+            },
+        ];
+
+        for desk in invalid {
+            assert!(desk.validate().is_err(), "{:?}", desk);
+        }
+    }
+
+    #[test]
+    fn test_serial_desk_writes_sequence() {
+        let pair = pty::openpty(None, None).unwrap();
+        let config = DeskControl {
+            enabled: true,
+            device: unistd::ttyname(&pair.slave).unwrap(),
+            sit: None,
+            stand: Some(DeskCommand::Bytes("F1 F1 06 00 06 7E".to_string())),
+            ..DeskControl::default()
+        };
+        let (sender, inbox) = mpsc::channel();
+        let mut desk = SerialDesk::open(&config, sender).unwrap();
+
+        desk.move_to(State::Sitting);
+        desk.move_to(State::Standing);
+
+        // Kept open until the end, closing it fails the drain of the write
+        let mut master = File::from(pair.master);
+        let mut received = [0; 6];
+        master.read_exact(&mut received).unwrap();
+        assert_eq!(received, [0xF1, 0xF1, 0x06, 0x00, 0x06, 0x7E]);
+        assert!(inbox.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn test_serial_desk_missing_device() {
+        let config = DeskControl {
+            device: PathBuf::from("/dev/get-up-missing-desk"),
+            ..DeskControl::default()
+        };
+
+        assert!(matches!(
+            SerialDesk::open(&config, mpsc::channel().0),
+            Err(DeskError::Open(..))
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+use std::{fmt, time::Duration};
+
+use serde::{de, Deserializer};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseDurationError(String);
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid duration \"{}\", expected a value such as 1h30m, 90m or 5400s",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+/// Parses durations written as a sequence of `<number><unit>` pairs where the
+/// unit is one of `h`, `m` or `s`, such as `1h30m`, `90m` or `5400s`.
+pub fn parse_duration(text: &str) -> Result<Duration, ParseDurationError> {
+    let error = || ParseDurationError(text.to_string());
+    let trimmed = text.trim();
+
+    if trimmed.is_empty() {
+        return Err(error());
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+
+    for character in trimmed.chars() {
+        if character.is_ascii_digit() {
+            digits.push(character);
+            continue;
+        }
+
+        let multiplier = match character {
+            'h' | 'H' => 3600,
+            'm' | 'M' => 60,
+            's' | 'S' => 1,
+            _ => return Err(error()),
+        };
+
+        let value: u64 = digits.parse().map_err(|_| error())?;
+        digits.clear();
+
+        total = value
+            .checked_mul(multiplier)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(error)?;
+    }
+
+    if !digits.is_empty() {
+        return Err(error());
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
+/// Formats a duration in the same notation accepted by [`parse_duration`],
+/// omitting the units that are zero.
+pub fn format_duration(duration: Duration) -> String {
+    let hours = duration.as_secs() / 3600;
+    let minutes = (duration.as_secs() / 60) % 60;
+    let seconds = duration.as_secs() % 60;
+
+    let mut text = String::new();
+    if hours > 0 {
+        text.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        text.push_str(&format!("{}m", minutes));
+    }
+    if seconds > 0 || text.is_empty() {
+        text.push_str(&format!("{}s", seconds));
+    }
+
+    text
+}
+
+/// Serde helper reading durations written as human-readable strings.
+pub fn deserialize_human<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let text: String = de::Deserialize::deserialize(deserializer)?;
+
+    parse_duration(&text).map_err(de::Error::custom)
+}
+
+/// Serde helper reading optional durations, for keys that enable a feature when present.
+pub fn deserialize_human_option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserialize_human(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_hours_minutes() {
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+    }
+
+    #[test]
+    fn test_parse_duration_minutes() {
+        assert_eq!(parse_duration("90m"), Ok(Duration::from_secs(5400)));
+    }
+
+    #[test]
+    fn test_parse_duration_seconds() {
+        assert_eq!(parse_duration("5400s"), Ok(Duration::from_secs(5400)));
+    }
+
+    #[test]
+    fn test_parse_duration_missing_unit() {
+        assert!(parse_duration("90").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_unknown_unit() {
+        assert!(parse_duration("2d").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_empty() {
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(5400)), "1h30m");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h");
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+    }
+}
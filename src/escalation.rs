@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::duration;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Additional alert added at every escalation level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Step {
+    /// Sends the reminder again through the notifiers
+    Notify,
+    /// Rings the terminal bell
+    Bell,
+    /// Flashes the border of the timer block
+    Flash,
+}
+
+/// Alerts repeated while a stance change waits for its acknowledgement, every step being
+/// added to the previous ones after each interval.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Escalation {
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub interval: Duration,
+    pub steps: Vec<Step>,
+}
+
+impl Default for Escalation {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL,
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl Escalation {
+    pub fn is_due(&self, awaiting: &Awaiting) -> bool {
+        !self.steps.is_empty() && awaiting.reminded_at.elapsed() >= self.interval
+    }
+
+    /// Steps performed at `level`, the first escalation being level 1.
+    pub fn steps(&self, level: usize) -> &[Step] {
+        &self.steps[..level.min(self.steps.len())]
+    }
+
+    pub fn is_flashing(&self, awaiting: &Awaiting) -> bool {
+        self.steps(awaiting.level).contains(&Step::Flash)
+    }
+}
+
+/// A stance change waiting to be acknowledged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Awaiting {
+    /// Index of the stance waiting to start.
+    pub stance: usize,
+    pub reminded_at: Instant,
+    pub level: usize,
+}
+
+impl Awaiting {
+    pub fn new(stance: usize) -> Self {
+        Self {
+            stance,
+            reminded_at: Instant::now(),
+            level: 0,
+        }
+    }
+
+    pub fn escalate(&mut self) {
+        self.level += 1;
+        self.reminded_at = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escalation() -> Escalation {
+        Escalation {
+            interval: Duration::from_secs(60),
+            steps: vec![Step::Notify, Step::Bell, Step::Flash],
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut awaiting = Awaiting::new(1);
+        assert!(!escalation().is_due(&awaiting));
+
+        awaiting.reminded_at = Instant::now() - Duration::from_secs(61);
+        assert!(escalation().is_due(&awaiting));
+    }
+
+    #[test]
+    fn test_is_due_without_steps() {
+        let mut awaiting = Awaiting::new(1);
+        awaiting.reminded_at = Instant::now() - Duration::from_secs(3600);
+
+        assert!(!Escalation::default().is_due(&awaiting));
+    }
+
+    #[test]
+    fn test_steps_accumulate() {
+        let escalation = escalation();
+
+        assert_eq!(escalation.steps(0), &[]);
+        assert_eq!(escalation.steps(2), &[Step::Notify, Step::Bell]);
+        assert_eq!(
+            escalation.steps(5),
+            &[Step::Notify, Step::Bell, Step::Flash]
+        );
+    }
+
+    #[test]
+    fn test_is_flashing() {
+        let mut awaiting = Awaiting::new(0);
+        awaiting.escalate();
+        awaiting.escalate();
+        assert!(!escalation().is_flashing(&awaiting));
+
+        awaiting.escalate();
+        assert!(escalation().is_flashing(&awaiting));
+    }
+
+    #[test]
+    fn test_parse_escalation() {
+        let escalation: Escalation =
+            toml::from_str("interval = \"2m\"\nsteps = [\"notify\", \"flash\"]").unwrap();
+
+        assert_eq!(escalation.interval, Duration::from_secs(120));
+        assert_eq!(escalation.steps, vec![Step::Notify, Step::Flash]);
+    }
+}
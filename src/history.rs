@@ -0,0 +1,266 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{paths, stats::Span, State};
+
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndReason {
+    Timer,
+    Skip,
+    Quit,
+}
+
+/// A completed stance period, stored as one JSON line in the history file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub stance: State,
+    /// Name of the stance, empty in the records written before the stances were configurable.
+    #[serde(default)]
+    pub name: String,
+    pub ended_by: EndReason,
+    pub paused_seconds: u64,
+    /// Number of times the change to another stance was snoozed during this period.
+    #[serde(default)]
+    pub snoozes: u32,
+}
+
+/// The stance period currently in progress.
+#[derive(Debug)]
+pub struct Period {
+    stance: State,
+    name: String,
+    start: DateTime<Local>,
+    paused: Duration,
+    paused_since: Option<Instant>,
+    snoozes: u32,
+}
+
+impl Period {
+    pub fn new(name: &str, stance: State) -> Self {
+        Self {
+            stance,
+            name: name.to_string(),
+            start: Local::now(),
+            paused: Duration::default(),
+            paused_since: None,
+            snoozes: 0,
+        }
+    }
+
+    /// A period of `name` that started at `start` and was paused for `paused` so far.
+    pub fn restore(name: &str, stance: State, start: DateTime<Local>, paused: Duration) -> Self {
+        Self {
+            stance,
+            name: name.to_string(),
+            start,
+            paused,
+            paused_since: None,
+            snoozes: 0,
+        }
+    }
+
+    pub fn snoozes(&self) -> u32 {
+        self.snoozes
+    }
+
+    /// Turns the period into the continuation of `name` after its change was snoozed.
+    pub fn snooze(&mut self, name: &str, stance: State, snoozes: u32) {
+        self.stance = stance;
+        self.name = name.to_string();
+        self.snoozes = snoozes;
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused_since.is_none() {
+            self.paused_since = Some(Instant::now());
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(paused_since) = self.paused_since.take() {
+            self.paused = self.paused.saturating_add(paused_since.elapsed());
+        }
+    }
+
+    pub fn paused(&self) -> Duration {
+        match self.paused_since {
+            Some(paused_since) => self.paused.saturating_add(paused_since.elapsed()),
+            None => self.paused,
+        }
+    }
+
+    /// The period so far, as if it ended now.
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.start,
+            end: Local::now(),
+            stance: self.stance,
+            name: self.name.clone(),
+            paused: self.paused(),
+        }
+    }
+
+    pub fn finish(&self, ended_by: EndReason) -> Record {
+        Record {
+            start: self.start,
+            end: Local::now(),
+            stance: self.stance,
+            name: self.name.clone(),
+            ended_by,
+            paused_seconds: self.paused().as_secs(),
+            snoozes: self.snoozes,
+        }
+    }
+}
+
+/// Append-only JSON Lines store of the completed stance periods.
+#[derive(Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    /// Reads every record, an absent file being an empty history.
+    pub fn load(&self) -> io::Result<Vec<Record>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+
+        let mut records = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = serde_json::from_str(&line).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} line {}: {}", path.display(), index + 1, err),
+                )
+            })?;
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
+    pub fn append(&self, record: &Record) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(HISTORY_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stance, test_dir::TestDir};
+
+    #[test]
+    fn test_period_paused_time() {
+        let mut period = Period::new(stance::SITTING, State::Sitting);
+        period.paused_since = Some(Instant::now() - Duration::from_secs(60));
+
+        period.resume();
+
+        assert_eq!(period.paused().as_secs(), 60);
+        assert_eq!(period.finish(EndReason::Skip).paused_seconds, 60);
+    }
+
+    #[test]
+    fn test_period_finish() {
+        let period = Period::new(stance::STANDING, State::Standing);
+
+        let record = period.finish(EndReason::Timer);
+
+        assert_eq!(record.stance, State::Standing);
+        assert_eq!(record.name, stance::STANDING);
+        assert_eq!(record.ended_by, EndReason::Timer);
+        assert!(record.start <= record.end);
+    }
+
+    #[test]
+    fn test_period_snooze() {
+        let mut period = Period::new(stance::STANDING, State::Standing);
+
+        period.snooze("kneeling", State::Sitting, 2);
+
+        let record = period.finish(EndReason::Timer);
+        assert_eq!(record.stance, State::Sitting);
+        assert_eq!(record.name, "kneeling");
+        assert_eq!(record.snoozes, 2);
+    }
+
+    #[test]
+    fn test_record_without_snoozes() {
+        let record: Record = serde_json::from_str(
+            r#"{"start":"2024-09-02T09:00:00-04:00","end":"2024-09-02T10:00:00-04:00","stance":"sitting","ended_by":"timer","paused_seconds":0}"#,
+        )
+        .unwrap();
+
+        assert_eq!(record.snoozes, 0);
+        assert_eq!(record.name, "");
+    }
+
+    #[test]
+    fn test_append_writes_json_lines() {
+        let dir = TestDir::new("history");
+        let path = dir.join(HISTORY_FILE_NAME);
+        let history = History::new(Some(path.clone()));
+        let record = Period::new(stance::SITTING, State::Sitting).finish(EndReason::Quit);
+
+        history.append(&record).unwrap();
+        history.append(&record).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert_eq!(history.load().unwrap(), vec![record.clone(), record]);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let history = History::new(Some(std::env::temp_dir().join("get-up-missing.jsonl")));
+
+        assert_eq!(history.load().unwrap(), Vec::new());
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fmt,
+    io::{self, Read},
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+
+use crate::{
+    duration::{self, format_duration},
+    Message, State,
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shell commands run when the stance changes or the timer is paused or resumed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    pub on_stand: Option<String>,
+    pub on_sit: Option<String>,
+    pub on_pause: Option<String>,
+    pub on_resume: Option<String>,
+    /// How long a hook may run before it is killed.
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub timeout: Duration,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self {
+            on_stand: None,
+            on_sit: None,
+            on_pause: None,
+            on_resume: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Stand,
+    Sit,
+    Pause,
+    Resume,
+}
+
+impl HookEvent {
+    /// Event of a period of `stance` starting.
+    pub fn started(stance: State) -> Self {
+        match stance {
+            State::Standing => HookEvent::Stand,
+            State::Sitting => HookEvent::Sit,
+        }
+    }
+
+    /// Configuration key of the hook.
+    pub fn key(self) -> &'static str {
+        match self {
+            HookEvent::Stand => "on_stand",
+            HookEvent::Sit => "on_sit",
+            HookEvent::Pause => "on_pause",
+            HookEvent::Resume => "on_resume",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::Stand => "stand",
+            HookEvent::Sit => "sit",
+            HookEvent::Pause => "pause",
+            HookEvent::Resume => "resume",
+        }
+    }
+}
+
+/// State of the timer passed to a hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookContext {
+    /// Name of the current stance.
+    pub stance: String,
+    pub posture: State,
+    /// Time left in the current stance.
+    pub duration: Duration,
+    /// When the current stance ends, unknown while paused.
+    pub end_time: Option<DateTime<Local>>,
+}
+
+#[derive(Debug)]
+pub enum HookError {
+    Spawn(io::Error),
+    Wait(io::Error),
+    Failed(ExitStatus, String),
+    TimedOut(Duration),
+}
+
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookError::Spawn(err) => write!(f, "could not be started: {}", err),
+            HookError::Wait(err) => write!(f, "could not be waited for: {}", err),
+            HookError::Failed(status, stderr) if stderr.is_empty() => {
+                write!(f, "failed with {}", status)
+            }
+            HookError::Failed(status, stderr) => write!(f, "failed with {}: {}", status, stderr),
+            HookError::TimedOut(timeout) => {
+                write!(f, "was killed after {}", format_duration(*timeout))
+            }
+        }
+    }
+}
+
+impl std::error::Error for HookError {}
+
+impl Hooks {
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Stand => self.on_stand.as_deref(),
+            HookEvent::Sit => self.on_sit.as_deref(),
+            HookEvent::Pause => self.on_pause.as_deref(),
+            HookEvent::Resume => self.on_resume.as_deref(),
+        }
+    }
+
+    /// Runs the hook of `event` in the background, if any, reporting its failure to `sender`.
+    pub fn run(&self, event: HookEvent, context: &HookContext, sender: &Sender<Message>) {
+        let Some(command) = self.command(event) else {
+            return;
+        };
+
+        let command = command.to_string();
+        let env = environment(event, context);
+        let timeout = self.timeout;
+        let sender = sender.clone();
+        thread::spawn(move || {
+            if let Err(err) = execute(&command, &env, timeout) {
+                let _ = sender.send(Message::Error(format!("The {} hook {}", event.key(), err)));
+            }
+        });
+    }
+}
+
+fn environment(event: HookEvent, context: &HookContext) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("GET_UP_EVENT", event.name().to_string()),
+        ("GET_UP_STANCE", context.stance.clone()),
+        ("GET_UP_POSTURE", context.posture.name().to_string()),
+        ("GET_UP_DURATION", format_duration(context.duration)),
+        (
+            "GET_UP_DURATION_SECONDS",
+            context.duration.as_secs().to_string(),
+        ),
+    ];
+
+    if let Some(end_time) = context.end_time {
+        env.push(("GET_UP_END_TIME", end_time.to_rfc3339()));
+    }
+
+    env
+}
+
+/// Runs `command` with `sh -c` until it exits, killing it after `timeout`.
+fn execute(command: &str, env: &[(&str, String)], timeout: Duration) -> Result<(), HookError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(HookError::Spawn)?;
+
+    // Read in the background so that a chatty hook does not block on a full pipe
+    let mut stderr = child.stderr.take();
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stderr) = &mut stderr {
+            let _ = stderr.read_to_string(&mut output);
+        }
+        output
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(HookError::Wait)? {
+            Some(status) => break status,
+            None if start.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(HookError::TimedOut(timeout));
+            }
+            None => thread::sleep(WAIT_INTERVAL),
+        }
+    };
+
+    if status.success() {
+        return Ok(());
+    }
+
+    let output = reader.join().unwrap_or_default();
+    let last_line = output.lines().rev().find(|line| !line.trim().is_empty());
+
+    Err(HookError::Failed(
+        status,
+        last_line.unwrap_or_default().trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn context() -> HookContext {
+        HookContext {
+            stance: "walking".to_string(),
+            posture: State::Standing,
+            duration: Duration::from_secs(1800),
+            end_time: None,
+        }
+    }
+
+    #[test]
+    fn test_execute_passes_environment() {
+        let env = environment(HookEvent::Stand, &context());
+        let command = r#"test "$GET_UP_EVENT $GET_UP_STANCE $GET_UP_POSTURE $GET_UP_DURATION $GET_UP_DURATION_SECONDS" = "stand walking standing 30m 1800" && test -z "$GET_UP_END_TIME""#;
+
+        assert!(execute(command, &env, DEFAULT_TIMEOUT).is_ok());
+    }
+
+    #[test]
+    fn test_execute_reports_failure() {
+        let err = execute("echo 'desk not found' >&2; exit 3", &[], DEFAULT_TIMEOUT).unwrap_err();
+
+        assert!(matches!(&err, HookError::Failed(status, stderr)
+            if status.code() == Some(3) && stderr == "desk not found"));
+    }
+
+    #[test]
+    fn test_execute_kills_after_timeout() {
+        let start = Instant::now();
+        let err = execute("sleep 10", &[], Duration::from_millis(100)).unwrap_err();
+
+        assert!(matches!(err, HookError::TimedOut(_)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_run_sends_error() {
+        let hooks = Hooks {
+            on_pause: Some("exit 1".to_string()),
+            ..Hooks::default()
+        };
+        let (sender, inbox) = mpsc::channel();
+
+        hooks.run(HookEvent::Resume, &context(), &sender);
+        hooks.run(HookEvent::Pause, &context(), &sender);
+
+        let message = inbox.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(message, Message::Error(error) if error.starts_with("The on_pause hook")));
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let hooks: Hooks = toml::from_str("on_stand = \"desk up\"\ntimeout = \"5s\"").unwrap();
+
+        assert_eq!(hooks.command(HookEvent::Stand), Some("desk up"));
+        assert_eq!(hooks.command(HookEvent::Sit), None);
+        assert_eq!(hooks.timeout, Duration::from_secs(5));
+    }
+}
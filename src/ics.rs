@@ -0,0 +1,218 @@
+use std::fmt;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use ical::property::Property;
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// A value of an iCalendar event that get-up cannot read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsError {
+    Recurring,
+    UnknownZone(String),
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for IcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcsError::Recurring => write!(f, "recurring events are not supported"),
+            IcsError::UnknownZone(id) => write!(f, "unknown time zone {}", id),
+            IcsError::Invalid(name, value) => write!(f, "invalid {} {:?}", name, value),
+        }
+    }
+}
+
+impl std::error::Error for IcsError {}
+
+pub fn property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    properties.iter().find(|property| property.name == name)
+}
+
+pub fn value<'a>(properties: &'a [Property], name: &str) -> Option<&'a str> {
+    property(properties, name)?.value.as_deref()
+}
+
+pub fn text(properties: &[Property], name: &str) -> Option<String> {
+    value(properties, name).map(unescape)
+}
+
+/// Date and time of a DATE-TIME property, `None` for dates.
+pub fn date_time(property: &Property) -> Result<Option<DateTime<Local>>, IcsError> {
+    let param = |name: &str| {
+        property
+            .params
+            .iter()
+            .flatten()
+            .find(|(param, _)| param == name)
+            .and_then(|(_, values)| values.first())
+    };
+    if param("VALUE").is_some_and(|value| value == "DATE") {
+        return Ok(None);
+    }
+
+    let text = property.value.as_deref().unwrap_or_default().trim();
+    let invalid = || IcsError::Invalid("date and time", text.to_string());
+
+    if let Some(utc) = text.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, DATE_TIME_FORMAT).map_err(|_| invalid())?;
+        return Ok(Some(Utc.from_utc_datetime(&time).with_timezone(&Local)));
+    }
+
+    let time = NaiveDateTime::parse_from_str(text, DATE_TIME_FORMAT).map_err(|_| invalid())?;
+    // The VTIMEZONE of the file is ignored in favour of the time zone database
+    let time = match param("TZID") {
+        Some(id) => {
+            let zone: Tz = id
+                .parse()
+                .map_err(|_| IcsError::UnknownZone(id.to_string()))?;
+            resolve(&zone, time)
+        }
+        None => resolve(&Local, time),
+    };
+
+    time.map(Some).ok_or_else(invalid)
+}
+
+/// `time` on a clock of `zone`, an hour later when daylight saving time skips it.
+fn resolve<Z: TimeZone>(zone: &Z, time: NaiveDateTime) -> Option<DateTime<Local>> {
+    [time, time + TimeDelta::hours(1)]
+        .into_iter()
+        .find_map(|time| zone.from_local_datetime(&time).earliest())
+        .map(|time| time.with_timezone(&Local))
+}
+
+/// Parses a duration such as `PT1H30M` or `P1W`.
+pub fn parse_duration(text: &str) -> Option<TimeDelta> {
+    let text = text.strip_prefix('+').unwrap_or(text).strip_prefix('P')?;
+    let mut total = TimeDelta::zero();
+    let mut number = String::new();
+
+    for character in text.chars() {
+        let unit = match character {
+            'T' => continue,
+            '0'..='9' => {
+                number.push(character);
+                continue;
+            }
+            'W' => TimeDelta::weeks(1),
+            'D' => TimeDelta::days(1),
+            'H' => TimeDelta::hours(1),
+            'M' => TimeDelta::minutes(1),
+            'S' => TimeDelta::seconds(1),
+            _ => return None,
+        };
+        total += unit * number.parse::<i32>().ok()?;
+        number.clear();
+    }
+
+    Some(total)
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut characters = text.chars();
+
+    while let Some(character) = characters.next() {
+        match (character, characters.clone().next()) {
+            ('\\', Some('n' | 'N')) => {
+                unescaped.push(' ');
+                characters.next();
+            }
+            ('\\', Some(escaped @ (',' | ';' | '\\'))) => {
+                unescaped.push(escaped);
+                characters.next();
+            }
+            _ => unescaped.push(character),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::America::Montreal;
+
+    use super::*;
+
+    fn dtstart(value: &str, params: &[(&str, &str)]) -> Property {
+        Property {
+            name: "DTSTART".to_string(),
+            params: Some(
+                params
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), vec![value.to_string()]))
+                    .collect(),
+            ),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_date_time() {
+        let local = |hour| Local.with_ymd_and_hms(2024, 9, 2, hour, 0, 0).unwrap();
+
+        assert_eq!(
+            date_time(&dtstart("20240902T090000", &[])),
+            Ok(Some(local(9)))
+        );
+        assert_eq!(
+            date_time(&dtstart("20240902T130000Z", &[])),
+            Ok(Some(
+                Utc.with_ymd_and_hms(2024, 9, 2, 13, 0, 0).unwrap().into()
+            ))
+        );
+        assert_eq!(
+            date_time(&dtstart("20240902", &[("VALUE", "DATE")])),
+            Ok(None)
+        );
+        assert_eq!(
+            date_time(&dtstart("soon", &[])),
+            Err(IcsError::Invalid("date and time", "soon".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_date_time_in_time_zone() {
+        let montreal = |month, day, hour| {
+            Montreal
+                .with_ymd_and_hms(2024, month, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+        let tzid = [("TZID", "America/Montreal")];
+
+        assert_eq!(
+            date_time(&dtstart("20241003T140000", &tzid)),
+            Ok(Some(montreal(10, 3, 14)))
+        );
+        // The offset changes with daylight saving time
+        assert_eq!(
+            date_time(&dtstart("20241104T140000", &tzid)),
+            Ok(Some(
+                Utc.with_ymd_and_hms(2024, 11, 4, 19, 0, 0).unwrap().into()
+            ))
+        );
+        // Skipped when daylight saving time starts
+        assert_eq!(
+            date_time(&dtstart("20240310T023000", &tzid)),
+            Ok(Some(montreal(3, 10, 3) + TimeDelta::minutes(30)))
+        );
+        assert_eq!(
+            date_time(&dtstart(
+                "20241003T140000",
+                &[("TZID", "Eastern Standard Time")]
+            )),
+            Err(IcsError::UnknownZone("Eastern Standard Time".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("P1W"), Some(TimeDelta::weeks(1)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+}
@@ -0,0 +1,425 @@
+use std::{env, fmt, sync::mpsc::Sender, thread, time::Duration};
+
+use serde::Deserialize;
+use x11rb::{
+    connection::Connection,
+    errors::{ConnectError, ReplyError},
+    protocol::screensaver::ConnectionExt,
+    rust_connection::RustConnection,
+};
+use zbus::proxy::CacheProperties;
+
+use crate::{duration, Hold, Message, ReturnPolicy};
+
+const DEFAULT_THRESHOLD: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Longest wait between two attempts to query a failing idle source.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+const MUTTER_DESTINATION: &str = "org.gnome.Mutter.IdleMonitor";
+const MUTTER_PATH: &str = "/org/gnome/Mutter/IdleMonitor/Core";
+const MUTTER_INTERFACE: &str = "org.gnome.Mutter.IdleMonitor";
+
+const SCREENSAVER_DESTINATION: &str = "org.freedesktop.ScreenSaver";
+const SCREENSAVER_PATH: &str = "/org/freedesktop/ScreenSaver";
+const SCREENSAVER_INTERFACE: &str = "org.freedesktop.ScreenSaver";
+
+/// Where the time since the last user input is read from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleSourceKind {
+    /// The D-Bus source of the Wayland compositor, X11 otherwise
+    #[default]
+    Auto,
+    X11,
+    Mutter,
+    Freedesktop,
+}
+
+/// Pauses the timer while no input was received for `threshold`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdleDetection {
+    pub enabled: bool,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub threshold: Duration,
+    pub source: IdleSourceKind,
+    pub on_return: ReturnPolicy,
+}
+
+impl Default for IdleDetection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: DEFAULT_THRESHOLD,
+            source: IdleSourceKind::default(),
+            on_return: ReturnPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IdleError {
+    X11Connect(ConnectError),
+    X11(ReplyError),
+    DBus(zbus::Error),
+}
+
+impl fmt::Display for IdleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdleError::X11Connect(err) => write!(f, "could not connect to the X server: {}", err),
+            IdleError::X11(err) => write!(f, "could not query the X11 idle time: {}", err),
+            IdleError::DBus(err) => write!(f, "could not query the idle time over D-Bus: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for IdleError {}
+
+pub trait IdleSource: Send {
+    fn idle_time(&mut self) -> Result<Duration, IdleError>;
+
+    fn reconnect(&mut self) -> Result<(), IdleError>;
+}
+
+/// Connects to the idle source selected in the configuration.
+pub fn connect(kind: IdleSourceKind) -> Result<Box<dyn IdleSource>, IdleError> {
+    let kind = match kind {
+        IdleSourceKind::Auto if env::var_os("WAYLAND_DISPLAY").is_some() => {
+            let connection = zbus::blocking::Connection::session().map_err(IdleError::DBus)?;
+            wayland_source(&connection).map_err(IdleError::DBus)?
+        }
+        IdleSourceKind::Auto => IdleSourceKind::X11,
+        kind => kind,
+    };
+
+    match kind {
+        IdleSourceKind::Mutter => Ok(Box::new(DBusIdleSource::connect(DBusIdleService::Mutter)?)),
+        IdleSourceKind::Freedesktop => Ok(Box::new(DBusIdleSource::connect(
+            DBusIdleService::Freedesktop,
+        )?)),
+        _ => Ok(Box::new(X11IdleSource::connect()?)),
+    }
+}
+
+/// The compositor's source over D-Bus, falling back to Xwayland.
+fn wayland_source(connection: &zbus::blocking::Connection) -> zbus::Result<IdleSourceKind> {
+    let dbus = zbus::blocking::fdo::DBusProxy::new(connection)?;
+
+    for (destination, kind) in [
+        (MUTTER_DESTINATION, IdleSourceKind::Mutter),
+        (SCREENSAVER_DESTINATION, IdleSourceKind::Freedesktop),
+    ] {
+        if dbus.name_has_owner(destination.try_into()?)? {
+            return Ok(kind);
+        }
+    }
+
+    Ok(IdleSourceKind::X11)
+}
+
+/// Idle time reported by the MIT-SCREEN-SAVER extension of the X server.
+pub struct X11IdleSource {
+    connection: RustConnection,
+    root: u32,
+}
+
+impl X11IdleSource {
+    pub fn connect() -> Result<Self, IdleError> {
+        let (connection, screen) = x11rb::connect(None).map_err(IdleError::X11Connect)?;
+        let root = connection.setup().roots[screen].root;
+
+        Ok(Self { connection, root })
+    }
+}
+
+impl IdleSource for X11IdleSource {
+    fn idle_time(&mut self) -> Result<Duration, IdleError> {
+        let info = self
+            .connection
+            .screensaver_query_info(self.root)
+            .map_err(|err| IdleError::X11(err.into()))?
+            .reply()
+            .map_err(IdleError::X11)?;
+
+        Ok(Duration::from_millis(info.ms_since_user_input.into()))
+    }
+
+    fn reconnect(&mut self) -> Result<(), IdleError> {
+        *self = Self::connect()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DBusIdleService {
+    Mutter,
+    Freedesktop,
+}
+
+/// Idle time reported over D-Bus by the desktop.
+pub struct DBusIdleSource {
+    service: DBusIdleService,
+    proxy: zbus::blocking::Proxy<'static>,
+}
+
+impl DBusIdleSource {
+    pub fn connect(service: DBusIdleService) -> Result<Self, IdleError> {
+        let connection = zbus::blocking::Connection::session().map_err(IdleError::DBus)?;
+
+        Self::on(&connection, service).map_err(IdleError::DBus)
+    }
+
+    fn on(connection: &zbus::blocking::Connection, service: DBusIdleService) -> zbus::Result<Self> {
+        let (destination, path, interface) = match service {
+            DBusIdleService::Mutter => (MUTTER_DESTINATION, MUTTER_PATH, MUTTER_INTERFACE),
+            DBusIdleService::Freedesktop => (
+                SCREENSAVER_DESTINATION,
+                SCREENSAVER_PATH,
+                SCREENSAVER_INTERFACE,
+            ),
+        };
+        let proxy = zbus::blocking::ProxyBuilder::new(connection)
+            .destination(destination)?
+            .path(path)?
+            .interface(interface)?
+            .cache_properties(CacheProperties::No)
+            .build()?;
+
+        Ok(Self { service, proxy })
+    }
+}
+
+impl IdleSource for DBusIdleSource {
+    fn idle_time(&mut self) -> Result<Duration, IdleError> {
+        let milliseconds = match self.service {
+            DBusIdleService::Mutter => self.proxy.call::<_, _, u64>("GetIdletime", &()),
+            // In milliseconds too, as KDE Plasma reports it
+            DBusIdleService::Freedesktop => self
+                .proxy
+                .call::<_, _, u32>("GetSessionIdleTime", &())
+                .map(u64::from),
+        }
+        .map_err(IdleError::DBus)?;
+
+        Ok(Duration::from_millis(milliseconds))
+    }
+
+    fn reconnect(&mut self) -> Result<(), IdleError> {
+        *self = Self::connect(self.service)?;
+
+        Ok(())
+    }
+}
+
+/// Turns idle times into a hold when they cross the threshold and a release once input resumes.
+#[derive(Debug)]
+pub struct IdleDetector {
+    threshold: Duration,
+    idle: bool,
+}
+
+impl IdleDetector {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            idle: false,
+        }
+    }
+
+    pub fn poll(&mut self, source: &mut dyn IdleSource) -> Result<Option<Message>, IdleError> {
+        let idle = source.idle_time()? >= self.threshold;
+
+        if idle == self.idle {
+            return Ok(None);
+        }
+
+        self.idle = idle;
+        Ok(Some(if idle {
+            Message::Hold(Hold::Idle)
+        } else {
+            Message::Release(Hold::Idle)
+        }))
+    }
+
+    /// Forgets about the idle time, releasing the hold it put on the timer, if any.
+    pub fn reset(&mut self) -> Option<Message> {
+        std::mem::take(&mut self.idle).then_some(Message::Release(Hold::Idle))
+    }
+}
+
+/// Polls `source` until the receiver is gone, releasing the timer and backing off while it fails.
+pub fn spawn_monitor(
+    mut source: Box<dyn IdleSource>,
+    threshold: Duration,
+    sender: Sender<Message>,
+) {
+    thread::spawn(move || {
+        let mut detector = IdleDetector::new(threshold);
+        let mut retry_interval = None;
+
+        loop {
+            let messages = match detector.poll(source.as_mut()) {
+                Ok(message) => {
+                    retry_interval = None;
+                    Vec::from_iter(message)
+                }
+                Err(err) => {
+                    // Reported once, when the source starts failing
+                    let error = retry_interval.is_none().then(|| {
+                        Message::Error(format!("Idle detection failed, retrying: {}", err))
+                    });
+                    retry_interval =
+                        Some(retry_interval.map_or(POLL_INTERVAL, |interval: Duration| {
+                            interval.saturating_mul(2).min(MAX_RETRY_INTERVAL)
+                        }));
+
+                    detector.reset().into_iter().chain(error).collect()
+                }
+            };
+
+            for message in messages {
+                if sender.send(message).is_err() {
+                    return;
+                }
+            }
+
+            match retry_interval {
+                Some(interval) => {
+                    thread::sleep(interval);
+                    // A failed connection fails the next query, which retries later
+                    let _ = source.reconnect();
+                }
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::mpsc};
+
+    use super::*;
+    use crate::test_bus::TestBus;
+
+    struct FakeIdleSource(VecDeque<Option<Duration>>);
+
+    impl FakeIdleSource {
+        fn new(seconds: &[Option<u64>]) -> Self {
+            Self(
+                seconds
+                    .iter()
+                    .map(|seconds| seconds.map(Duration::from_secs))
+                    .collect(),
+            )
+        }
+    }
+
+    impl IdleSource for FakeIdleSource {
+        fn idle_time(&mut self) -> Result<Duration, IdleError> {
+            self.0
+                .pop_front()
+                .flatten()
+                .ok_or(IdleError::DBus(zbus::Error::Failure(
+                    "no idle time".to_string(),
+                )))
+        }
+
+        fn reconnect(&mut self) -> Result<(), IdleError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poll_holds_once_over_threshold() {
+        let mut source = FakeIdleSource::new(&[Some(10), Some(299), Some(300), Some(360)]);
+        let mut detector = IdleDetector::new(Duration::from_secs(300));
+
+        assert!(detector.poll(&mut source).unwrap().is_none());
+        assert!(detector.poll(&mut source).unwrap().is_none());
+        assert!(matches!(
+            detector.poll(&mut source).unwrap(),
+            Some(Message::Hold(Hold::Idle))
+        ));
+        assert!(detector.poll(&mut source).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_poll_releases_on_input() {
+        let mut source = FakeIdleSource::new(&[Some(600), Some(0), Some(1)]);
+        let mut detector = IdleDetector::new(Duration::from_secs(300));
+
+        detector.poll(&mut source).unwrap();
+
+        assert!(matches!(
+            detector.poll(&mut source).unwrap(),
+            Some(Message::Release(Hold::Idle))
+        ));
+        assert!(detector.poll(&mut source).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_monitor_releases_and_retries_on_failure() {
+        let (sender, inbox) = mpsc::channel();
+
+        spawn_monitor(
+            Box::new(FakeIdleSource::new(&[Some(600), None, None, Some(600)])),
+            Duration::from_secs(300),
+            sender,
+        );
+        let receive = || inbox.recv_timeout(Duration::from_secs(10)).unwrap();
+
+        assert!(matches!(receive(), Message::Hold(Hold::Idle)));
+        assert!(matches!(receive(), Message::Release(Hold::Idle)));
+        assert!(matches!(receive(), Message::Error(_)));
+        assert!(matches!(receive(), Message::Hold(Hold::Idle)));
+    }
+
+    struct FakeScreenSaver;
+
+    #[zbus::interface(name = "org.freedesktop.ScreenSaver")]
+    impl FakeScreenSaver {
+        fn get_session_idle_time(&self) -> u32 {
+            90_000
+        }
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_wayland_source_prefers_desktop_service() {
+        let bus = TestBus::start();
+        let connection = bus.connect();
+
+        assert_eq!(wayland_source(&connection).unwrap(), IdleSourceKind::X11);
+
+        let screensaver = bus.connect();
+        screensaver
+            .object_server()
+            .at(SCREENSAVER_PATH, FakeScreenSaver)
+            .unwrap();
+        screensaver.request_name(SCREENSAVER_DESTINATION).unwrap();
+
+        assert_eq!(
+            wayland_source(&connection).unwrap(),
+            IdleSourceKind::Freedesktop
+        );
+        let mut source = DBusIdleSource::on(&connection, DBusIdleService::Freedesktop).unwrap();
+        assert_eq!(source.idle_time().unwrap(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_parse_idle_detection() {
+        let idle: IdleDetection = toml::from_str(
+            "enabled = true\nthreshold = \"10m\"\nsource = \"x11\"\non_return = \"reset\"",
+        )
+        .unwrap();
+
+        assert!(idle.enabled);
+        assert_eq!(idle.threshold, Duration::from_secs(600));
+        assert_eq!(idle.source, IdleSourceKind::X11);
+        assert_eq!(idle.on_return, ReturnPolicy::Reset);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{env, sync::mpsc::Sender, thread};
+
+use serde::Deserialize;
+use zbus::{
+    blocking::{proxy::SignalIterator, Connection, Proxy, ProxyBuilder},
+    proxy::CacheProperties,
+    zvariant::OwnedObjectPath,
+};
+
+use crate::{Hold, Message, ReturnPolicy};
+
+const DESTINATION: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+/// Pauses the timer while the session is locked or the system is asleep.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LockDetection {
+    pub enabled: bool,
+    pub on_return: ReturnPolicy,
+}
+
+/// Connects to logind on the system bus and forwards the sleep and lock signals of the current
+/// session.
+pub fn spawn_monitor(sender: Sender<Message>) -> zbus::Result<()> {
+    let connection = Connection::system()?;
+    let session = session_path(&connection)?;
+
+    watch(&connection, session, sender)
+}
+
+/// Object path of the session get-up runs in, falling back to the display session of the user.
+fn session_path(connection: &Connection) -> zbus::Result<OwnedObjectPath> {
+    let id = env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+
+    proxy(connection, MANAGER_PATH, MANAGER_INTERFACE)?.call("GetSession", &(id,))
+}
+
+/// Forwards `PrepareForSleep` from the logind manager and `Lock`/`Unlock` from `session` as holds.
+pub fn watch(
+    connection: &Connection,
+    session: OwnedObjectPath,
+    sender: Sender<Message>,
+) -> zbus::Result<()> {
+    let sleep_signals =
+        proxy(connection, MANAGER_PATH, MANAGER_INTERFACE)?.receive_signal("PrepareForSleep")?;
+    let session_signals = proxy(connection, session, SESSION_INTERFACE)?.receive_all_signals()?;
+
+    forward(sleep_signals, sender.clone(), sleep_message);
+    forward(session_signals, sender, lock_message);
+
+    Ok(())
+}
+
+fn proxy<'a, P>(connection: &Connection, path: P, interface: &'a str) -> zbus::Result<Proxy<'a>>
+where
+    P: TryInto<zbus::zvariant::ObjectPath<'a>>,
+    P::Error: Into<zbus::Error>,
+{
+    ProxyBuilder::new(connection)
+        .destination(DESTINATION)?
+        .path(path)?
+        .interface(interface)?
+        .cache_properties(CacheProperties::No)
+        .build()
+}
+
+fn forward(
+    signals: SignalIterator<'static>,
+    sender: Sender<Message>,
+    to_message: fn(&zbus::Message) -> Option<Message>,
+) {
+    thread::spawn(move || {
+        for signal in signals {
+            if let Some(message) = to_message(&signal) {
+                if sender.send(message).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+}
+
+fn sleep_message(signal: &zbus::Message) -> Option<Message> {
+    match signal.body().deserialize::<bool>() {
+        Ok(true) => Some(Message::Hold(Hold::Sleep)),
+        Ok(false) => Some(Message::Release(Hold::Sleep)),
+        Err(_) => None,
+    }
+}
+
+fn lock_message(signal: &zbus::Message) -> Option<Message> {
+    match signal.header().member()?.as_str() {
+        "Lock" => Some(Message::Hold(Hold::Locked)),
+        "Unlock" => Some(Message::Release(Hold::Locked)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::mpsc::{self, Receiver},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::test_bus::TestBus;
+
+    const SESSION_PATH: &str = "/org/freedesktop/login1/session/_31";
+
+    /// Starts a bus with a fake logind owning its name.
+    fn logind() -> (TestBus, Connection, Receiver<Message>) {
+        let bus = TestBus::start();
+        let logind = bus.connect_as(DESTINATION);
+
+        let (sender, inbox) = mpsc::channel();
+        watch(
+            &bus.connect(),
+            OwnedObjectPath::try_from(SESSION_PATH).unwrap(),
+            sender,
+        )
+        .unwrap();
+
+        (bus, logind, inbox)
+    }
+
+    fn receive(inbox: &Receiver<Message>) -> Message {
+        inbox.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_watch_sleep() {
+        let (_bus, logind, inbox) = logind();
+
+        for start in [true, false] {
+            logind
+                .emit_signal(
+                    None::<()>,
+                    MANAGER_PATH,
+                    MANAGER_INTERFACE,
+                    "PrepareForSleep",
+                    &(start,),
+                )
+                .unwrap();
+        }
+
+        assert!(matches!(receive(&inbox), Message::Hold(Hold::Sleep)));
+        assert!(matches!(receive(&inbox), Message::Release(Hold::Sleep)));
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn test_watch_lock() {
+        let (_bus, logind, inbox) = logind();
+
+        for (path, member) in [
+            ("/org/freedesktop/login1/session/_32", "Lock"),
+            (SESSION_PATH, "Lock"),
+            (SESSION_PATH, "Unlock"),
+        ] {
+            logind
+                .emit_signal(None::<()>, path, SESSION_INTERFACE, member, &())
+                .unwrap();
+        }
+
+        assert!(matches!(receive(&inbox), Message::Hold(Hold::Locked)));
+        assert!(matches!(receive(&inbox), Message::Release(Hold::Locked)));
+    }
+}
@@ -1,28 +1,77 @@
+mod achievements;
+mod calendar;
+mod cli;
+mod config;
+mod ctl;
+mod daemon;
+mod dbus;
+mod desk;
+mod duration;
+mod escalation;
+mod history;
+mod hooks;
+mod ics;
+mod idle;
+mod logind;
 mod notification;
+mod paths;
 mod pausable_timer;
-
+mod pomodoro;
+mod ramp_up;
+mod report;
+mod schedule;
+mod stance;
+mod stats;
+mod statusbar;
+#[cfg(test)]
+mod test_bus;
+#[cfg(test)]
+mod test_dir;
+
+use achievements::{format_days, Achievements};
+use calendar::{Agenda, Event};
+use clap::{Parser, ValueEnum};
+use cli::{Cli, Command};
+use config::{Config, ConfigError};
+use daemon::{Client, PomodoroStatus, Reply, Request, Status};
+use desk::{DeskController, SerialDesk};
+use escalation::{Awaiting, Escalation, Step};
+use history::{EndReason, History, Period, Record};
+use hooks::{HookContext, HookEvent, Hooks};
+use notification::{Modal, Notifier, Reminder, ReminderKind};
 use pausable_timer::Timer;
+use pomodoro::{Phase, Pomodoro, Session};
+use ramp_up::Program;
 use ratatui::{
     crossterm::event::{self, KeyCode, KeyEventKind},
-    layout::Alignment,
+    layout::{Alignment, Flex, Rect},
     prelude::{symbols, Constraint, Direction, Layout},
     style::{Color, Style, Stylize},
     symbols::border,
     text::Line,
     widgets::{
         block::{Position, Title},
-        Block, LineGauge, Padding,
+        Bar, BarChart, BarGroup, Block, Clear, LineGauge, Padding, Paragraph,
     },
     Frame,
 };
-use std::{io, time::Duration};
+use schedule::Schedule;
+use serde::{Deserialize, Serialize};
+use stance::Stance;
+use stats::{DailyStats, Span};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    io,
+    path::Path,
+    process::ExitCode,
+    sync::mpsc::{self, Receiver, Sender},
+    time::{Duration, Instant},
+};
 
-const INCREASE_STEP_DURATION: Duration = Duration::from_secs(300);
 const POLL_DURATION: Duration = Duration::from_millis(1000);
-const MAX_DURATION: Duration = Duration::from_secs(14400);
-const MIN_DURATION: Duration = Duration::from_secs(300); 
-const DEFAULT_SITTING_DURATION: Duration = Duration::from_secs(3600);
-const DEFAULT_STANDING_DURATION: Duration = Duration::from_secs(1800);
+/// How long a warning stays under the timer.
+const WARNING_DURATION: Duration = Duration::from_secs(60);
 
 const TITLE_STYLE: Style = Style::new().fg(Color::LightCyan);
 const SELECTED_STYLE: Style = Style::new().fg(Color::Rgb(202, 166, 247));
@@ -30,17 +79,79 @@ const UNSELECTED_STYLE: Style = Style::new().fg(Color::DarkGray);
 const IN_PROGRESS_GAUGE_STYLE: Style = Style::new().fg(Color::Green);
 const PAUSED_GAUGE_STYLE: Style = Style::new().fg(Color::Yellow);
 const SETTINGS_GAUGE_STYLE: Style = Style::new().fg(Color::Blue);
-
-#[derive(Debug, Default)]
+const FLASH_STYLE: Style = Style::new().fg(Color::LightRed);
+const STATUS_STYLE: Style = Style::new().fg(Color::Red);
+const SITTING_BAR_STYLE: Style = Style::new().fg(Color::Blue);
+const STANDING_BAR_STYLE: Style = Style::new().fg(Color::Green);
+const GOAL_GAUGE_STYLE: Style = Style::new().fg(Color::LightGreen);
+const WORK_GAUGE_STYLE: Style = Style::new().fg(Color::LightRed);
+const BREAK_GAUGE_STYLE: Style = Style::new().fg(Color::LightBlue);
+
+#[derive(Debug)]
 struct Model {
-    state: State,
+    stances: Vec<Stance>,
+    /// Indices in `stances` of the stances of the cycle, in order.
+    cycle: Vec<usize>,
+    /// Position of the current stance in `cycle`.
+    position: usize,
     timer_state: TimerState,
-    sitting_duration: Duration,
-    standing_duration: Duration,
+    step_duration: Duration,
+    min_duration: Duration,
+    max_duration: Duration,
+    snooze_duration: Duration,
+    /// Duration of the current period when it continues a snoozed stance.
+    snoozed_duration: Option<Duration>,
+    /// Snoozes of the period ended by the timer, while its stance change can be snoozed: until
+    /// the snooze duration elapsed in the new stance.
+    snoozable: Option<u32>,
+    require_acknowledgement: bool,
+    /// Standing time to reach every day, when set.
+    standing_goal: Option<Duration>,
+    /// Day on which the standing goal was last reached, to celebrate it once.
+    goal_reached_on: Option<chrono::NaiveDate>,
+    /// Ramp-up program setting the duration of a stance, when enabled.
+    ramp_up: Option<Program>,
+    /// Work and break cycle, when enabled.
+    pomodoro: Option<Pomodoro>,
+    session: Session,
+    /// Stance change waiting for the user to confirm it.
+    awaiting: Option<Awaiting>,
+    escalation: Escalation,
+    /// Automatic pauses in effect, on top of the one toggled by the user.
+    holds: Vec<Hold>,
+    idle_return: ReturnPolicy,
+    lock_return: ReturnPolicy,
+    /// Working hours, when enabled.
+    schedule: Option<Schedule>,
+    /// Meetings of the calendar files, when there are some.
+    agenda: Option<Agenda>,
+    hooks: Hooks,
 
     running_state: RunningState,
     selected_widget_block: WidgetBlock,
     timer: Timer,
+    /// Timer of the current phase of the Pomodoro cycle.
+    pomodoro_timer: Timer,
+    period: Period,
+    history: History,
+    records: Vec<Record>,
+    /// Standing time of the days before the records of today, for the streaks and milestones.
+    past_standing: BTreeMap<chrono::NaiveDate, Duration>,
+    show_statistics: bool,
+    status: Option<Warning>,
+    notifiers: Vec<Box<dyn Notifier>>,
+    modal: Modal,
+    sender: Sender<Message>,
+    inbox: Receiver<Message>,
+    /// Daemon running the timer when the TUI is attached to one.
+    daemon: Option<Client>,
+    /// Last warning reported by the daemon.
+    daemon_warning: Option<String>,
+    /// Ramp-up program run by the daemon.
+    daemon_ramp_up: Option<ramp_up::Progress>,
+    /// `org.getup.Timer` service on the session bus.
+    bus: Option<dbus::Service>,
+    desk: Option<Box<dyn DeskController>>,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -50,13 +161,23 @@ enum RunningState {
     Done,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 enum State {
     #[default]
     Sitting,
     Standing,
 }
 
+impl State {
+    fn name(self) -> &'static str {
+        match self {
+            State::Sitting => stance::SITTING,
+            State::Standing => stance::STANDING,
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 enum TimerState {
     #[default]
@@ -64,14 +185,67 @@ enum TimerState {
     Paused,
 }
 
+/// Reason for pausing the timer without the user asking for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Hold {
+    Idle,
+    Locked,
+    Sleep,
+    OffHours,
+}
+
+impl Hold {
+    fn label(self) -> &'static str {
+        match self {
+            Hold::Idle => "[IDLE]",
+            Hold::Locked => "[LOCKED]",
+            Hold::Sleep => "[ASLEEP]",
+            Hold::OffHours => "[OFF HOURS]",
+        }
+    }
+}
+
+/// Error shown under the timer until it expires, such as a failed notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Warning {
+    text: String,
+    shown_at: Instant,
+}
+
+impl Warning {
+    fn new(text: String) -> Self {
+        Self {
+            text,
+            shown_at: Instant::now(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.shown_at.elapsed() >= WARNING_DURATION
+    }
+}
+
+/// What happens to the stance timer once a hold is released.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ReturnPolicy {
+    /// Continue where the timer was paused
+    #[default]
+    Resume,
+    /// Start the current stance over
+    Reset,
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 enum WidgetBlock {
     #[default]
     Timer,
-    SittingSettings,
-    StandingSettings,
+    /// Duration of the stance at this index in `Model::stances`
+    Settings(usize),
 }
 
+#[derive(Debug)]
 enum Message {
     Increase,
     Decrease,
@@ -82,25 +256,195 @@ enum Message {
     Resume,
     NavigateForward,
     NavigateBackward,
+    ToggleStatistics,
+    DismissModal,
+    Snooze,
+    Acknowledge,
+    /// Button of a notification raised in the period starting at the given time.
+    ReminderAction(
+        notification::Action,
+        Option<chrono::DateTime<chrono::Local>>,
+    ),
+    Escalate,
     TimerFinished,
+    GoalReached,
+    AdvanceRampUp,
+    PhaseFinished,
+    SkipPhase,
+    Hold(Hold),
+    Release(Hold),
+    Error(String),
+    ClearWarning,
+    Control(Request, Sender<Reply>),
+    Sync,
+    Meetings(Vec<Event>),
 }
 
-fn main() -> io::Result<()> {
-    let mut terminal = ratatui::init();
-    let _ = terminal.clear()?;
+impl Model {
+    fn new(config: &Config) -> Self {
+        let modal = Modal::default();
+        let (sender, inbox) = mpsc::channel();
+        let stances = config.stances();
+        let cycle = config.cycle();
+        let position = cycle
+            .iter()
+            .position(|&index| stances[index].name == config.start_stance)
+            .unwrap_or_default();
+        let start = &stances[cycle[position]];
+        let period = Period::new(&start.name, start.posture);
+
+        Self {
+            stances,
+            cycle,
+            position,
+            timer_state: TimerState::default(),
+            step_duration: config.step_duration,
+            min_duration: config.min_duration,
+            max_duration: config.max_duration,
+            snooze_duration: config.snooze_duration,
+            snoozed_duration: None,
+            snoozable: None,
+            require_acknowledgement: config.require_acknowledgement,
+            standing_goal: config.standing_goal,
+            goal_reached_on: None,
+            ramp_up: None,
+            pomodoro: config.pomodoro.enabled.then(|| config.pomodoro.clone()),
+            session: Session::default(),
+            awaiting: None,
+            escalation: config.escalation.clone(),
+            holds: Vec::new(),
+            idle_return: config.idle.on_return,
+            lock_return: config.lock.on_return,
+            schedule: config.schedule.enabled.then(|| config.schedule.clone()),
+            agenda: (!config.calendar.files.is_empty()).then(|| Agenda::new(&config.calendar)),
+            hooks: config.hooks.clone(),
+            running_state: RunningState::default(),
+            selected_widget_block: WidgetBlock::default(),
+            timer: Timer::default(),
+            pomodoro_timer: Timer::default(),
+            period,
+            history: History::default(),
+            records: Vec::new(),
+            past_standing: BTreeMap::new(),
+            show_statistics: false,
+            status: None,
+            notifiers: notification::notifiers(
+                &config.notifiers,
+                config.notify_command.as_deref(),
+                &modal,
+                &sender,
+            ),
+            modal,
+            sender,
+            inbox,
+            daemon: None,
+            daemon_warning: None,
+            daemon_ramp_up: None,
+            bus: None,
+            desk: None,
+        }
+    }
+}
+
+impl Default for Model {
+    /// A model of the default configuration that sends the reminders nowhere.
+    fn default() -> Self {
+        Self::new(&Config {
+            notifiers: Vec::new(),
+            ..Config::default()
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("get-up: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    match &cli.command {
+        Some(Command::Ctl(args)) => return ctl::run(args),
+        Some(Command::Status(args)) => return statusbar::run(args),
+        _ => {}
+    }
+
+    let config_path = cli.config.clone().or_else(config::default_path);
+    let mut config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    cli.apply(&mut config)?;
+    config.validate()?;
+
+    if let Some(Command::Report(args)) = &cli.command {
+        return report::run(args, config.standing_goal);
+    }
+
+    // The daemon has no terminal to ring or draw the reminders in
+    if let Some(Command::Daemon) = &cli.command {
+        if config.notifiers.iter().any(|kind| kind.needs_terminal()) {
+            eprintln!("get-up: the daemon ignores the bell and modal notifiers");
+            config.notifiers.retain(|kind| !kind.needs_terminal());
+        }
+    }
+
+    let mut model = Model::new(&config);
+    model.history = History::new(history::default_path());
+    load_today(&mut model);
+    start_calendar(&config, &model);
+
+    // The ramp-up program is run by the process owning the timer
+    if let Some(Command::Daemon) = &cli.command {
+        start_ramp_up(&config, &mut model);
+        start_monitors(&config, &mut model);
+        start_service(&config, &mut model);
+        open_desk(&config, &mut model);
+        daemon::run(&mut model)?;
+    } else {
+        match daemon::socket_path().map(|path| Client::connect(&path)) {
+            Some(Ok(client)) => {
+                model.daemon = Some(client);
+                update(&mut model, Message::Sync);
+            }
+            _ => {
+                start_ramp_up(&config, &mut model);
+                start_monitors(&config, &mut model);
+                start_service(&config, &mut model);
+                open_desk(&config, &mut model);
+            }
+        }
+
+        run_terminal(&mut model)?;
+    }
+
+    // An attached daemon saves its own settings
+    if let (Some(path), None) = (&config_path, &model.daemon) {
+        save_settings(path, &config, &model)?;
+    }
+
+    Ok(())
+}
 
-    let mut model = Model::default();
-    model.sitting_duration = DEFAULT_SITTING_DURATION;
-    model.standing_duration = DEFAULT_STANDING_DURATION;
+fn run_terminal(model: &mut Model) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    terminal.clear()?;
 
     while model.running_state != RunningState::Done {
-        terminal.draw(|frame| view(&model, frame))?;
+        terminal.draw(|frame| view(model, frame))?;
 
-        let mut current_message = handle_events(&model)?;
+        let mut current_message = handle_events(model)?;
 
         while current_message.is_some() {
-            current_message = update(&mut model, current_message.unwrap());
+            current_message = update(model, current_message.unwrap());
         }
+        publish(model);
     }
 
     ratatui::restore();
@@ -108,40 +452,236 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
+/// Loads the records of today from the history, for the statistics overlay and the standing
+/// goal, and the standing time of the previous days.
+fn load_today(model: &mut Model) {
+    match model.history.load() {
+        Ok(records) => {
+            let today = chrono::Local::now().date_naive();
+            let (start_of_today, _) = stats::day_bounds(today);
+            let (records, past): (Vec<Record>, Vec<Record>) = records
+                .into_iter()
+                .partition(|record| record.end >= start_of_today);
+            let past: Vec<Span> = past.iter().map(Span::from).collect();
+            model.records = records;
+            model.past_standing = stats::standing_by_day(&past);
+
+            // The goal was celebrated before a restart
+            if goal_progress(model).is_some_and(|(standing, goal)| standing >= goal) {
+                model.goal_reached_on = Some(today);
+            }
+        }
+        Err(err) => {
+            model.status = Some(Warning::new(format!(
+                "Could not read the session history: {}",
+                err
+            )))
+        }
+    }
+}
+
+/// Starts the idle, lock and sleep monitors enabled in the configuration.
+fn start_monitors(config: &Config, model: &mut Model) {
+    if config.idle.enabled {
+        match idle::connect(config.idle.source) {
+            Ok(source) => idle::spawn_monitor(source, config.idle.threshold, model.sender.clone()),
+            Err(err) => {
+                model.status = Some(Warning::new(format!("Idle detection is disabled: {}", err)))
+            }
+        }
+    }
+
+    if config.lock.enabled {
+        if let Err(err) = logind::spawn_monitor(model.sender.clone()) {
+            model.status = Some(Warning::new(format!(
+                "Lock and sleep detection is disabled: {}",
+                err
+            )));
+        }
+    }
+}
+
+/// Sets the duration of the stance of the ramp-up program for today, when it is enabled.
+fn start_ramp_up(config: &Config, model: &mut Model) {
+    if !config.ramp_up.enabled {
+        return;
+    }
+    let Some(stance) = stance_index(model, &config.ramp_up.stance) else {
+        return;
+    };
+
+    match Program::start(
+        stance,
+        config.ramp_up.clone(),
+        ramp_up::Store::new(ramp_up::default_path()),
+        chrono::Local::now().date_naive(),
+    ) {
+        Ok(program) => {
+            model.stances[stance].duration = program.duration();
+            model.ramp_up = Some(program);
+        }
+        Err(err) => {
+            model.status = Some(Warning::new(format!(
+                "The ramp-up program is disabled: {}",
+                err
+            )))
+        }
+    }
+}
+
+/// Watches the calendar files, whose meetings are also shown by a TUI attached to a daemon.
+fn start_calendar(config: &Config, model: &Model) {
+    if model.agenda.is_some() {
+        calendar::spawn_monitor(config.calendar.files.clone(), model.sender.clone());
+    }
+}
+
+/// Registers the timer on the session bus when enabled in the configuration.
+fn start_service(config: &Config, model: &mut Model) {
+    if !config.dbus_service {
+        return;
+    }
+
+    match dbus::Service::start(model.sender.clone(), status(model)) {
+        Ok(service) => model.bus = Some(service),
+        Err(err) => {
+            model.status = Some(Warning::new(format!(
+                "The D-Bus service is disabled: {}",
+                err
+            )))
+        }
+    }
+}
+
+/// Opens the serial port of the desk when its control is enabled in the configuration.
+fn open_desk(config: &Config, model: &mut Model) {
+    if !config.desk.enabled {
+        return;
+    }
+
+    match SerialDesk::open(&config.desk, model.sender.clone()) {
+        Ok(desk) => model.desk = Some(Box::new(desk)),
+        Err(err) => model.status = Some(Warning::new(format!("Desk control is disabled: {}", err))),
+    }
+}
+
+/// Writes back the durations that were changed from the settings blocks, except the one set by
+/// the ramp-up program.
+fn save_settings(path: &Path, config: &Config, model: &Model) -> Result<(), ConfigError> {
+    let ramped = model.ramp_up.as_ref().map(|program| program.stance);
+    let changes: Vec<(usize, Duration)> = config
+        .stances()
+        .iter()
+        .zip(&model.stances)
+        .enumerate()
+        .filter(|(index, (configured, stance))| {
+            Some(*index) != ramped && configured.duration != stance.duration
+        })
+        .map(|(index, (_, stance))| (index, stance.duration))
+        .collect();
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    // Without `[[stances]]`, the durations are the top-level sitting and standing ones
+    if config.stances.is_empty() {
+        let keys: Vec<(&str, Duration)> = changes
+            .into_iter()
+            .map(|(index, duration)| match model.stances[index].posture {
+                State::Sitting => (config::SITTING_DURATION_KEY, duration),
+                State::Standing => (config::STANDING_DURATION_KEY, duration),
+            })
+            .collect();
+
+        return config::write_durations(path, &keys);
+    }
+
+    config::write_stance_durations(path, &changes)
+}
+
 fn view(model: &Model, frame: &mut Frame) {
+    let mut area = frame.area();
+
+    if let Some(warning) = &model.status {
+        let status_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+
+        area = status_chunks[0];
+        frame.render_widget(
+            Paragraph::new(warning.text.as_str()).style(STATUS_STYLE),
+            status_chunks[1],
+        );
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .spacing(1)
-        .split(frame.area());
+        .split(area);
+
+    let goal = goal_progress(model);
+    let timer_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            std::iter::once(Constraint::Fill(2))
+                .chain(model.pomodoro.as_ref().map(|_| Constraint::Fill(1)))
+                .chain(goal.map(|_| Constraint::Fill(1))),
+        )
+        .spacing(1)
+        .split(chunks[0]);
+    let mut side_chunks = timer_chunks.iter().skip(1);
 
     let settings_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints(
+            model
+                .stances
+                .iter()
+                .map(|_| Constraint::Ratio(1, model.stances.len() as u32)),
+        )
         .spacing(1)
         .split(chunks[1]);
 
-    let timer_duration = match model.state {
-        State::Sitting => model.sitting_duration,
-        State::Standing => model.standing_duration,
-    };
-    
-    let ratio = (model.timer.elapsed().as_secs_f64() / timer_duration.as_secs_f64())
-        .clamp(0.0, 1.0);
+    let timer_duration = current_duration(model);
+
+    let ratio =
+        (model.timer.elapsed().as_secs_f64() / timer_duration.as_secs_f64()).clamp(0.0, 1.0);
     let time_left = timer_duration.saturating_sub(model.timer.elapsed());
 
-    let progress_title = Title::from(
+    let progress_title = if model.holds.contains(&Hold::OffHours) {
+        match model
+            .schedule
+            .as_ref()
+            .and_then(|schedule| schedule.next_start(chrono::Local::now().naive_local()))
+        {
+            Some(start) => format!(" GET UP : Off hours until {} ", format_start(start)),
+            None => " GET UP : Off hours ".to_string(),
+        }
+    } else if let Some(awaiting) = &model.awaiting {
         format!(
-            " GET UP : {} until {} ",
-            if model.state == State::Sitting {
-                "Sitting"
+            " GET UP : Waiting for you to switch to {} ",
+            model.stances[awaiting.stance].name
+        )
+    } else {
+        format!(
+            " GET UP : {}{} until {}{} ",
+            current_stance(model).label(),
+            if model.snoozed_duration.is_some() {
+                " (snoozed)"
             } else {
-                "Standing"
+                ""
             },
-            format_time_after_duration(time_left)
+            stance_change_time(model).map_or_else(
+                || format_time_after_duration(time_left),
+                |time| time.format(LONG_TIME_FORMAT).to_string()
+            ),
+            upcoming_meeting(model)
         )
-        .bold(),
-    );
+    };
+    let progress_title = Title::from(progress_title.bold());
     let progress_instructions = Title::from(Line::from(vec![
         " Quit ".into(),
         "<Q>".blue().bold(),
@@ -150,7 +690,11 @@ fn view(model: &Model, frame: &mut Frame) {
         " Restart ".into(),
         "<H>".blue().bold(),
         " Next ".into(),
-        "<L> ".blue().bold(),
+        "<L>".blue().bold(),
+        " Snooze ".into(),
+        "<Z>".blue().bold(),
+        " Statistics ".into(),
+        "<S> ".blue().bold(),
     ]));
     let progress_block = Block::bordered()
         .title(progress_title.alignment(Alignment::Center))
@@ -160,7 +704,9 @@ fn view(model: &Model, frame: &mut Frame) {
                 .position(Position::Bottom),
         )
         .padding(Padding::uniform(1))
-        .border_style(if model.selected_widget_block == WidgetBlock::Timer {
+        .border_style(if is_flashing(model) {
+            FLASH_STYLE
+        } else if model.selected_widget_block == WidgetBlock::Timer {
             SELECTED_STYLE
         } else {
             UNSELECTED_STYLE
@@ -171,25 +717,38 @@ fn view(model: &Model, frame: &mut Frame) {
     frame.render_widget(
         LineGauge::default()
             .block(progress_block)
-            .filled_style(if model.timer_state == TimerState::InProgress {
-                IN_PROGRESS_GAUGE_STYLE
-            } else {
+            .filled_style(if is_paused(model) {
                 PAUSED_GAUGE_STYLE
+            } else {
+                IN_PROGRESS_GAUGE_STYLE
             })
             .line_set(symbols::line::DOUBLE)
-            .label(format!(
-                "{} {}",
-                if model.timer_state == TimerState::Paused {
-                    "[PAUSED]"
-                } else {
-                    "        "
-                },
-                format_duration_hours_minutes_seconds(time_left)
-            ))
+            .label(if model.awaiting.is_some() {
+                "[WAITING] Confirm <Enter>".to_string()
+            } else {
+                format!(
+                    "{:<8} {}",
+                    if model.timer_state == TimerState::Paused {
+                        "[PAUSED]"
+                    } else {
+                        model.holds.first().map_or("", |hold| hold.label())
+                    },
+                    format_duration_hours_minutes_seconds(time_left)
+                )
+            })
             .ratio(ratio),
-        chunks[0],
+        timer_chunks[0],
     );
 
+    if let (Some(pomodoro), Some(area)) = (&model.pomodoro, side_chunks.next()) {
+        view_pomodoro(model, pomodoro, frame, *area);
+    }
+
+    if let (Some((standing, goal)), Some(area)) = (goal, side_chunks.next()) {
+        let streak = achievements(model).current_streak.unwrap_or_default();
+        view_goal(standing, goal, streak, frame, *area);
+    }
+
     let settings_instructions = Title::from(Line::from(vec![
         " Decrease ".into(),
         "<H>".blue().bold(),
@@ -199,65 +758,316 @@ fn view(model: &Model, frame: &mut Frame) {
     .alignment(Alignment::Center)
     .position(Position::Bottom);
 
-    let sitting_settings_title = Title::from(" Sitting duration ".bold());
-    let sitting_settings_block = Block::bordered()
-        .title(sitting_settings_title.alignment(Alignment::Center))
-        .title(settings_instructions.clone())
+    let ramp_up = ramp_up_progress(model);
+    for (index, stance) in model.stances.iter().enumerate() {
+        let settings_title = Title::from(
+            match ramp_up
+                .as_ref()
+                .filter(|progress| progress.stance == stance.name)
+            {
+                Some(progress) => {
+                    format!(" {} duration : {} ", stance.label(), progress.describe())
+                }
+                None => format!(" {} duration ", stance.label()),
+            }
+            .bold(),
+        );
+        let settings_block = Block::bordered()
+            .title(settings_title.alignment(Alignment::Center))
+            .title(settings_instructions.clone())
+            .padding(Padding::uniform(1))
+            .border_style(
+                if model.selected_widget_block == WidgetBlock::Settings(index) {
+                    SELECTED_STYLE
+                } else {
+                    UNSELECTED_STYLE
+                },
+            )
+            .title_style(TITLE_STYLE)
+            .border_set(border::THICK);
+
+        frame.render_widget(
+            LineGauge::default()
+                .block(settings_block)
+                .filled_style(SETTINGS_GAUGE_STYLE)
+                .line_set(symbols::line::NORMAL)
+                .label(format_duration_hours_minutes(stance.duration))
+                .ratio(ratio_duration(
+                    stance.duration,
+                    model.min_duration,
+                    model.max_duration,
+                )),
+            settings_chunks[index],
+        );
+    }
+
+    if model.show_statistics {
+        view_statistics(model, frame, area);
+    }
+
+    if let Some(reminder) = model.modal.current() {
+        view_modal(&reminder, frame, area);
+    }
+}
+
+/// Whether the escalated timer border is in the highlighted half of its one second blink.
+fn is_flashing(model: &Model) -> bool {
+    model.awaiting.as_ref().is_some_and(|awaiting| {
+        model.escalation.is_flashing(awaiting) && awaiting.reminded_at.elapsed().as_secs() % 2 == 0
+    })
+}
+
+/// Gauge of the current phase of the Pomodoro cycle.
+fn view_pomodoro(model: &Model, pomodoro: &Pomodoro, frame: &mut Frame, area: Rect) {
+    let phase = model.session.phase;
+    let duration = pomodoro.duration(phase);
+    let elapsed = model.pomodoro_timer.elapsed();
+    let time_left = duration.saturating_sub(elapsed);
+
+    let title = match phase {
+        Phase::Work => format!(
+            " {} {}/{} ",
+            phase.label(),
+            pomodoro.set_position(model.session),
+            pomodoro.long_break_every
+        ),
+        Phase::ShortBreak | Phase::LongBreak => format!(" {} ", phase.label()),
+    };
+    let pomodoro_block = Block::bordered()
+        .title(Title::from(title.bold()).alignment(Alignment::Center))
+        .title(
+            Title::from(Line::from(vec![" Skip ".into(), "<P> ".blue().bold()]))
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
         .padding(Padding::uniform(1))
-        .border_style(
-            if model.selected_widget_block == WidgetBlock::SittingSettings {
-                SELECTED_STYLE
+        .border_style(UNSELECTED_STYLE)
+        .title_style(TITLE_STYLE)
+        .border_set(border::THICK);
+
+    frame.render_widget(
+        LineGauge::default()
+            .block(pomodoro_block)
+            .filled_style(if is_paused(model) {
+                PAUSED_GAUGE_STYLE
+            } else if phase.is_break() {
+                BREAK_GAUGE_STYLE
             } else {
-                UNSELECTED_STYLE
-            },
-        )
+                WORK_GAUGE_STYLE
+            })
+            .line_set(symbols::line::DOUBLE)
+            .label(format!(
+                "{:<8} {}",
+                if is_paused(model) { "[PAUSED]" } else { "" },
+                format_duration_hours_minutes_seconds(time_left)
+            ))
+            .ratio((elapsed.as_secs_f64() / duration.as_secs_f64()).clamp(0.0, 1.0)),
+        area,
+    );
+}
+
+/// Gauge of the standing time of today toward the daily goal, with the current streak.
+fn view_goal(standing: Duration, goal: Duration, streak: u32, frame: &mut Frame, area: Rect) {
+    let title = if streak > 0 {
+        format!(" Standing goal : {} streak ", format_days(streak))
+    } else {
+        " Standing goal ".to_string()
+    };
+    let goal_block = Block::bordered()
+        .title(Title::from(title.bold()).alignment(Alignment::Center))
+        .padding(Padding::uniform(1))
+        .border_style(UNSELECTED_STYLE)
         .title_style(TITLE_STYLE)
         .border_set(border::THICK);
 
     frame.render_widget(
         LineGauge::default()
-            .block(sitting_settings_block)
-            .filled_style(SETTINGS_GAUGE_STYLE)
-            .line_set(symbols::line::NORMAL)
-            .label(format_duration_hours_minutes(model.sitting_duration))
-            .ratio(ratio_duration(
-                model.sitting_duration,
-                MIN_DURATION,
-                MAX_DURATION,
-            )),
-        settings_chunks[0],
+            .block(goal_block)
+            .filled_style(GOAL_GAUGE_STYLE)
+            .line_set(symbols::line::DOUBLE)
+            .label(format!(
+                "{:<8} {} / {}",
+                if standing >= goal { "[DONE]" } else { "" },
+                format_duration_hours_minutes(standing),
+                format_duration_hours_minutes(goal)
+            ))
+            .ratio((standing.as_secs_f64() / goal.as_secs_f64()).clamp(0.0, 1.0)),
+        area,
     );
+}
 
-    let standing_settings_title = Title::from(" Standing duration ".bold());
-    let standing_settings_block = Block::bordered()
-        .title(standing_settings_title.alignment(Alignment::Center))
-        .title(settings_instructions.clone())
+fn view_modal(reminder: &Reminder, frame: &mut Frame, area: Rect) {
+    let modal_area = centered_area(area, 50, 30);
+    let modal_block = Block::bordered()
+        .title(Title::from(format!(" {} ", reminder.title).bold()).alignment(Alignment::Center))
+        .title(
+            Title::from(Line::from(vec![
+                " Dismiss ".into(),
+                "<Enter> ".blue().bold(),
+            ]))
+            .alignment(Alignment::Center)
+            .position(Position::Bottom),
+        )
         .padding(Padding::uniform(1))
-        .border_style(
-            if model.selected_widget_block == WidgetBlock::StandingSettings {
-                SELECTED_STYLE
-            } else {
-                UNSELECTED_STYLE
-            },
+        .border_style(SELECTED_STYLE)
+        .title_style(TITLE_STYLE)
+        .border_set(border::THICK);
+
+    frame.render_widget(Clear, modal_area);
+    frame.render_widget(
+        Paragraph::new(reminder.message.as_str())
+            .alignment(Alignment::Center)
+            .block(modal_block),
+        modal_area,
+    );
+}
+
+fn view_statistics(model: &Model, frame: &mut Frame, area: Rect) {
+    let stats = today_stats(model);
+    let achievements = achievements(model);
+
+    let overlay_area = centered_area(area, 70, 60);
+    let statistics_block = Block::bordered()
+        .title(Title::from(" Today ".bold()).alignment(Alignment::Center))
+        .title(
+            Title::from(Line::from(vec![" Close ".into(), "<S> ".blue().bold()]))
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
         )
+        .padding(Padding::uniform(1))
+        .border_style(SELECTED_STYLE)
         .title_style(TITLE_STYLE)
         .border_set(border::THICK);
 
+    let statistics_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .spacing(1)
+        .split(statistics_block.inner(overlay_area));
+
+    let ratio = match stats.sit_stand_ratio() {
+        Some(ratio) => format!("{:.1} : 1", ratio),
+        None => "-".to_string(),
+    };
+    let mut summary = vec![
+        Line::from(vec![
+            "Sitting ".into(),
+            format_duration_hours_minutes(stats.sitting).bold(),
+        ]),
+        Line::from(vec![
+            "Standing ".into(),
+            format_duration_hours_minutes(stats.standing).bold(),
+        ]),
+        Line::from(vec![
+            "Transitions ".into(),
+            stats.transitions.to_string().bold(),
+        ]),
+        Line::from(vec![
+            "Longest sitting stretch ".into(),
+            format_duration_hours_minutes(stats.longest_sitting).bold(),
+        ]),
+        Line::from(vec!["Sit/stand ratio ".into(), ratio.bold()]),
+        Line::default(),
+    ];
+    if let (Some(current), Some(longest)) =
+        (achievements.current_streak, achievements.longest_streak)
+    {
+        summary.push(Line::from(vec![
+            "Streak ".into(),
+            format_days(current).bold(),
+            format!(" (longest {})", format_days(longest)).into(),
+        ]));
+    }
+    summary.push(Line::from(vec![
+        "Standing in total ".into(),
+        format_duration_hours_minutes(Duration::from_secs(achievements.standing_seconds)).bold(),
+    ]));
+    if let Some(milestone) = achievements.milestones.last() {
+        summary.push(Line::from(vec![
+            "Last milestone ".into(),
+            format!("{}h", milestone.hours).bold(),
+            format!(" on {}", milestone.reached_on.format("%a %m-%d")).into(),
+        ]));
+    }
+    if let Some(hours) = achievements.next_milestone() {
+        summary.push(Line::from(vec![
+            "Next milestone ".into(),
+            format!("{}h", hours).bold(),
+        ]));
+    }
+
+    let bars = [
+        Bar::default()
+            .label("Sitting".into())
+            .value(stats.sitting.as_secs() / 60)
+            .text_value(format_duration_hours_minutes(stats.sitting))
+            .style(SITTING_BAR_STYLE),
+        Bar::default()
+            .label("Standing".into())
+            .value(stats.standing.as_secs() / 60)
+            .text_value(format_duration_hours_minutes(stats.standing))
+            .style(STANDING_BAR_STYLE),
+    ];
+
+    frame.render_widget(Clear, overlay_area);
+    frame.render_widget(statistics_block, overlay_area);
+    frame.render_widget(Paragraph::new(summary), statistics_chunks[0]);
     frame.render_widget(
-        LineGauge::default()
-            .block(standing_settings_block)
-            .filled_style(SETTINGS_GAUGE_STYLE)
-            .line_set(symbols::line::NORMAL)
-            .label(format_duration_hours_minutes(model.standing_duration))
-            .ratio(ratio_duration(
-                model.standing_duration,
-                MIN_DURATION,
-                MAX_DURATION,
-            )),
-        settings_chunks[1],
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(10)
+            .bar_gap(2),
+        statistics_chunks[1],
     );
 }
 
+/// Spans of the records of today and of the period in progress.
+fn today_spans(model: &Model) -> Vec<Span> {
+    model
+        .records
+        .iter()
+        .map(Span::from)
+        .chain(std::iter::once(model.period.span()))
+        .collect()
+}
+
+/// Statistics of today, including the period in progress.
+fn today_stats(model: &Model) -> DailyStats {
+    DailyStats::compute(chrono::Local::now().date_naive(), &today_spans(model))
+}
+
+/// Streaks and milestones over the whole history, including the period in progress.
+fn achievements(model: &Model) -> Achievements {
+    let mut days = model.past_standing.clone();
+    for (date, standing) in stats::standing_by_day(&today_spans(model)) {
+        *days.entry(date).or_default() += standing;
+    }
+
+    Achievements::compute(
+        &days,
+        model.standing_goal,
+        chrono::Local::now().date_naive(),
+    )
+}
+
+/// Standing time of today and the daily goal, when there is one.
+fn goal_progress(model: &Model) -> Option<(Duration, Duration)> {
+    let goal = model.standing_goal?;
+
+    Some((today_stats(model).standing, goal))
+}
+
+fn centered_area(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Percentage(width_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Percentage(height_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    area
+}
+
 fn handle_events(model: &Model) -> io::Result<Option<Message>> {
     if let Some(message) = handle_async(model) {
         return Ok(Some(message));
@@ -272,24 +1082,87 @@ fn handle_events(model: &Model) -> io::Result<Option<Message>> {
             }
         }
     }
+
+    if model.daemon.is_some() {
+        return Ok(Some(Message::Sync));
+    }
     Ok(None)
 }
 
 fn handle_async(model: &Model) -> Option<Message> {
-    let timer_duration = if model.state == State::Sitting {
-        model.sitting_duration
-    } else {
-        model.standing_duration
-    };
+    if let Ok(message) = model.inbox.try_recv() {
+        return Some(message);
+    }
+
+    if model.status.as_ref().is_some_and(Warning::is_expired) {
+        return Some(Message::ClearWarning);
+    }
+
+    // The timer of an attached daemon ends on its side
+    if model.daemon.is_some() {
+        return None;
+    }
+
+    if let Some(message) = check_schedule(model) {
+        return Some(message);
+    }
+
+    if goal_reached(model) {
+        return Some(Message::GoalReached);
+    }
+
+    if model
+        .ramp_up
+        .as_ref()
+        .is_some_and(|program| program.state.last_day < chrono::Local::now().date_naive())
+    {
+        return Some(Message::AdvanceRampUp);
+    }
+
+    if model.pomodoro.as_ref().is_some_and(|pomodoro| {
+        model.pomodoro_timer.elapsed() >= pomodoro.duration(model.session.phase)
+    }) {
+        return Some(Message::PhaseFinished);
+    }
 
-    if model.timer.elapsed() > timer_duration {
+    if let Some(awaiting) = &model.awaiting {
+        if model.escalation.is_due(awaiting) {
+            return Some(Message::Escalate);
+        }
+    } else if stance_change_due(model) {
         return Some(Message::TimerFinished);
     }
 
     None
 }
 
+/// Holds the timer outside of the working hours and releases it when they start again.
+fn check_schedule(model: &Model) -> Option<Message> {
+    let schedule = model.schedule.as_ref()?;
+    let off_hours = !schedule.is_working(chrono::Local::now().naive_local());
+
+    match (off_hours, model.holds.contains(&Hold::OffHours)) {
+        (true, false) => Some(Message::Hold(Hold::OffHours)),
+        (false, true) => Some(Message::Release(Hold::OffHours)),
+        _ => None,
+    }
+}
+
+/// Whether the standing goal was just reached, and not celebrated yet today.
+fn goal_reached(model: &Model) -> bool {
+    model.goal_reached_on != Some(chrono::Local::now().date_naive())
+        && goal_progress(model).is_some_and(|(standing, goal)| standing >= goal)
+}
+
 fn handle_key(model: &Model, key: crossterm::event::KeyEvent) -> Option<Message> {
+    if model.awaiting.is_some() && key.code == KeyCode::Enter {
+        return Some(Message::Acknowledge);
+    }
+
+    if model.modal.current().is_some() && matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+        return Some(Message::DismissModal);
+    }
+
     match key.code {
         KeyCode::Char('q') | KeyCode::Char('Q') => Some(Message::Quit),
         KeyCode::Char(' ') => {
@@ -299,6 +1172,11 @@ fn handle_key(model: &Model, key: crossterm::event::KeyEvent) -> Option<Message>
                 Some(Message::Pause)
             }
         }
+        KeyCode::Char('s') | KeyCode::Char('S') => Some(Message::ToggleStatistics),
+        KeyCode::Char('z') | KeyCode::Char('Z') => Some(Message::Snooze),
+        KeyCode::Char('p') | KeyCode::Char('P') if model.pomodoro.is_some() => {
+            Some(Message::SkipPhase)
+        }
         KeyCode::Tab => Some(Message::NavigateForward),
         KeyCode::BackTab => Some(Message::NavigateBackward),
         KeyCode::Char('h') | KeyCode::Char('H') => {
@@ -320,85 +1198,729 @@ fn handle_key(model: &Model, key: crossterm::event::KeyEvent) -> Option<Message>
 }
 
 fn update(model: &mut Model, message: Message) -> Option<Message> {
+    if let Some(request) = daemon_request(model, &message) {
+        send_to_daemon(model, &request);
+        return None;
+    }
+
     match message {
-        Message::Quit => model.running_state = RunningState::Done,
-        Message::Increase => match model.selected_widget_block {
-            WidgetBlock::SittingSettings => {
-                model.sitting_duration = model
-                    .sitting_duration
-                    .saturating_add(INCREASE_STEP_DURATION)
-                    .clamp(MIN_DURATION, MAX_DURATION);
+        Message::Quit => {
+            if model.daemon.is_none() {
+                record_period(model, EndReason::Quit);
             }
-            WidgetBlock::StandingSettings => {
-                model.standing_duration = model
-                    .standing_duration
-                    .saturating_add(INCREASE_STEP_DURATION)
-                    .clamp(MIN_DURATION, MAX_DURATION);
+            model.running_state = RunningState::Done;
+        }
+        Message::Increase | Message::Decrease => {
+            if let WidgetBlock::Settings(index) = model.selected_widget_block {
+                model.stances[index].duration =
+                    stepped_duration(model, index, matches!(message, Message::Increase));
             }
-            _ => {}
-        },
-        Message::Decrease => match model.selected_widget_block {
-            WidgetBlock::SittingSettings => {
-                model.sitting_duration = model
-                    .sitting_duration
-                    .saturating_sub(INCREASE_STEP_DURATION)
-                    .clamp(MIN_DURATION, MAX_DURATION);
-            }
-            WidgetBlock::StandingSettings => {
-                model.standing_duration = model
-                    .standing_duration
-                    .saturating_sub(INCREASE_STEP_DURATION)
-                    .clamp(MIN_DURATION, MAX_DURATION);
-            }
-            _ => {}
-        },
+        }
         Message::Pause => {
             model.timer_state = TimerState::Paused;
-            model.timer.pause();
+            sync_pause(model);
         }
         Message::Resume => {
             model.timer_state = TimerState::InProgress;
-            model.timer.resume();
+            sync_pause(model);
         }
         Message::NavigateForward => {
+            let last = model.stances.len() - 1;
             model.selected_widget_block = match model.selected_widget_block {
-                WidgetBlock::Timer => WidgetBlock::SittingSettings,
-                WidgetBlock::SittingSettings => WidgetBlock::StandingSettings,
-                WidgetBlock::StandingSettings => WidgetBlock::Timer,
+                WidgetBlock::Timer => WidgetBlock::Settings(0),
+                WidgetBlock::Settings(index) if index < last => WidgetBlock::Settings(index + 1),
+                WidgetBlock::Settings(_) => WidgetBlock::Timer,
             }
         }
         Message::NavigateBackward => {
+            let last = model.stances.len() - 1;
             model.selected_widget_block = match model.selected_widget_block {
-                WidgetBlock::Timer => WidgetBlock::StandingSettings,
-                WidgetBlock::SittingSettings => WidgetBlock::Timer,
-                WidgetBlock::StandingSettings => WidgetBlock::SittingSettings,
+                WidgetBlock::Timer => WidgetBlock::Settings(last),
+                WidgetBlock::Settings(0) => WidgetBlock::Timer,
+                WidgetBlock::Settings(index) => WidgetBlock::Settings(index - 1),
             }
         }
         Message::Next => {
             model.timer.reset_time();
-            model.state = match model.state {
-                State::Sitting => State::Standing,
-                State::Standing => State::Sitting,
-            };
+            model.awaiting = None;
+            model.snoozable = None;
+            model.snoozed_duration = None;
+            change_stance(model, EndReason::Skip);
         }
         Message::Reset => {
             model.timer.reset_time();
+            model.awaiting = None;
+            model.snoozable = None;
+            model.snoozed_duration = None;
+        }
+        Message::ToggleStatistics => {
+            model.show_statistics = !model.show_statistics;
+            // Periods are recorded by the daemon
+            if model.show_statistics && model.daemon.is_some() {
+                load_today(model);
+            }
+        }
+        Message::DismissModal => model.modal.dismiss(),
+        Message::Snooze => {
+            if model.awaiting.take().is_some() {
+                let snoozes = model.period.snoozes();
+                record_period(model, EndReason::Timer);
+                start_period(model);
+                let name = current_stance(model).name.clone();
+                model.period.snooze(&name, posture(model), snoozes + 1);
+                model.snoozed_duration = Some(model.snooze_duration);
+                model.timer.reset_time();
+                model.modal.withdraw(ReminderKind::StanceChange);
+            } else if let Some(snoozes) = model
+                .snoozable
+                .take()
+                .filter(|_| model.timer.elapsed() < model.snooze_duration)
+            {
+                let previous = posture(model);
+                model.position = (model.position + model.cycle.len() - 1) % model.cycle.len();
+                let name = current_stance(model).name.clone();
+                model.period.snooze(&name, posture(model), snoozes + 1);
+                model.snoozed_duration = Some(model.snooze_duration);
+                model.timer.reset_time();
+                model.modal.withdraw(ReminderKind::StanceChange);
+                announce_stance(model, previous);
+            }
+        }
+        Message::Acknowledge => {
+            if model.awaiting.take().is_some() {
+                model.timer.reset_time();
+                model.snoozed_duration = None;
+                change_stance(model, EndReason::Timer);
+                model.modal.withdraw(ReminderKind::StanceChange);
+            }
+        }
+        Message::ReminderAction(action, period_start) => {
+            // The notifications of the previous periods are stale
+            if period_start != Some(model.period.span().start) {
+                return None;
+            }
+
+            return Some(match action {
+                notification::Action::Snooze => Message::Snooze,
+                notification::Action::Acknowledge => Message::Acknowledge,
+            });
+        }
+        Message::Escalate => {
+            if let Some(awaiting) = &mut model.awaiting {
+                awaiting.escalate();
+                let stance = model.stances[awaiting.stance].clone();
+                let steps = model.escalation.steps(awaiting.level).to_vec();
+
+                if steps.contains(&Step::Notify) {
+                    notify(
+                        model,
+                        &Reminder::awaiting_acknowledgement(&stance, stance.duration),
+                    );
+                }
+                if steps.contains(&Step::Bell) {
+                    let _ = notification::ring_terminal_bell();
+                }
+            }
+        }
+        Message::Hold(hold) => {
+            if !model.holds.contains(&hold) {
+                model.holds.push(hold);
+            }
+            sync_pause(model);
+        }
+        Message::Release(hold) => {
+            if !model.holds.contains(&hold) {
+                return None;
+            }
+
+            model.holds.retain(|held| *held != hold);
+            sync_pause(model);
+
+            if return_policy(model, hold) == ReturnPolicy::Reset {
+                return Some(Message::Reset);
+            }
+        }
+        Message::Error(error) => model.status = Some(Warning::new(error)),
+        Message::ClearWarning => model.status = None,
+        Message::Control(request, reply) => {
+            let _ = reply.send(control(model, request));
+        }
+        Message::Sync => {}
+        Message::GoalReached => {
+            model.goal_reached_on = Some(chrono::Local::now().date_naive());
+            if let Some(goal) = model.standing_goal {
+                notify(model, &Reminder::goal_reached(goal));
+            }
+        }
+        Message::AdvanceRampUp => {
+            if let Some(program) = &mut model.ramp_up {
+                match program.advance(chrono::Local::now().date_naive()) {
+                    Ok(true) => {
+                        let (stance, duration) = (program.stance, program.duration());
+                        model.stances[stance].duration = duration;
+                    }
+                    Ok(false) => {}
+                    Err(err) => {
+                        model.ramp_up = None;
+                        model.status = Some(Warning::new(format!(
+                            "The ramp-up program is disabled: {}",
+                            err
+                        )));
+                    }
+                }
+            }
+        }
+        Message::PhaseFinished | Message::SkipPhase => {
+            let pomodoro = model.pomodoro.clone()?;
+
+            model.session = pomodoro.next(model.session);
+            model.pomodoro_timer.reset_time();
+
+            if matches!(message, Message::PhaseFinished) {
+                let phase = model.session.phase;
+                let stance = current_stance(model).clone();
+                notify(
+                    model,
+                    &Reminder::phase_started(&stance, phase, pomodoro.duration(phase)),
+                );
+            }
+        }
+        Message::Meetings(events) => {
+            if let Some(agenda) = &mut model.agenda {
+                agenda.events = events;
+            }
+        }
+        Message::TimerFinished if model.require_acknowledgement => {
+            let target = model.cycle[next_position(model)];
+            let stance = model.stances[target].clone();
+            model.awaiting = Some(Awaiting::new(target));
+            notify(
+                model,
+                &Reminder::awaiting_acknowledgement(&stance, stance.duration),
+            );
         }
         Message::TimerFinished => {
+            let snoozes = model.period.snoozes();
             model.timer.reset_time();
-            model.state = match model.state {
-                State::Sitting => State::Standing,
-                State::Standing => State::Sitting,
+            model.snoozed_duration = None;
+            change_stance(model, EndReason::Timer);
+            model.snoozable = Some(snoozes);
+
+            let stance = current_stance(model).clone();
+            notify(model, &Reminder::new(&stance, stance.duration));
+        }
+    }
+
+    None
+}
+
+/// Applies a request received on the control socket, answering with the resulting status.
+fn control(model: &mut Model, request: Request) -> Reply {
+    let message = match request {
+        Request::Pause => Message::Pause,
+        Request::Resume => Message::Resume,
+        Request::Toggle if model.timer_state == TimerState::Paused => Message::Resume,
+        Request::Toggle => Message::Pause,
+        Request::Next => Message::Next,
+        Request::Reset => Message::Reset,
+        Request::Snooze => Message::Snooze,
+        Request::Acknowledge => Message::Acknowledge,
+        Request::SkipPhase if model.pomodoro.is_none() => {
+            return Reply::Error("the Pomodoro cycle is not enabled".to_string());
+        }
+        Request::SkipPhase => Message::SkipPhase,
+        Request::Quit => Message::Quit,
+        Request::Status => Message::Sync,
+        Request::Increase(name) => {
+            let Some(stance) = stance_index(model, &name) else {
+                return unknown_stance(model, &name);
             };
 
-            match model.state {
-                State::Sitting => notification::send_sit_notification(model.sitting_duration),
-                State::Standing => notification::send_stand_notification(model.standing_duration),
+            model.stances[stance].duration = stepped_duration(model, stance, true);
+            Message::Sync
+        }
+        Request::Decrease(name) => {
+            let Some(stance) = stance_index(model, &name) else {
+                return unknown_stance(model, &name);
             };
+
+            model.stances[stance].duration = stepped_duration(model, stance, false);
+            Message::Sync
         }
+        Request::SetDuration(name, duration) => {
+            let Some(stance) = stance_index(model, &name) else {
+                return unknown_stance(model, &name);
+            };
+
+            if duration < model.min_duration || duration > model.max_duration {
+                return Reply::Error(format!(
+                    "the duration must be between {} and {}",
+                    duration::format_duration(model.min_duration),
+                    duration::format_duration(model.max_duration)
+                ));
+            }
+
+            model.stances[stance].duration = duration;
+            Message::Sync
+        }
+    };
+
+    let mut current_message = Some(message);
+    while let Some(message) = current_message {
+        current_message = update(model, message);
     }
 
-    None
+    Reply::Status(status(model))
+}
+
+fn unknown_stance(model: &Model, name: &str) -> Reply {
+    let names: Vec<&str> = model
+        .stances
+        .iter()
+        .map(|stance| stance.name.as_str())
+        .collect();
+
+    Reply::Error(format!(
+        "unknown stance \"{}\", expected one of {}",
+        name,
+        names.join(", ")
+    ))
+}
+
+/// Request replacing `message` when the TUI is attached to a daemon, which runs the timer.
+fn daemon_request(model: &Model, message: &Message) -> Option<Request> {
+    model.daemon.as_ref()?;
+
+    match message {
+        Message::Pause => Some(Request::Pause),
+        Message::Resume => Some(Request::Resume),
+        Message::Next => Some(Request::Next),
+        Message::Reset => Some(Request::Reset),
+        Message::Snooze => Some(Request::Snooze),
+        Message::Acknowledge => Some(Request::Acknowledge),
+        Message::SkipPhase => Some(Request::SkipPhase),
+        Message::Sync => Some(Request::Status),
+        Message::Increase => {
+            selected_stance(model).map(|stance| Request::Increase(stance.name.clone()))
+        }
+        Message::Decrease => {
+            selected_stance(model).map(|stance| Request::Decrease(stance.name.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn send_to_daemon(model: &mut Model, request: &Request) {
+    let Some(client) = &mut model.daemon else {
+        return;
+    };
+
+    match client.send(request) {
+        Ok(Reply::Status(status)) => apply_status(model, status),
+        Ok(Reply::Error(error)) => model.status = Some(Warning::new(error)),
+        Err(err) => {
+            model.status = Some(Warning::new(format!(
+                "Lost the connection to the daemon: {}",
+                err
+            )))
+        }
+    }
+}
+
+fn status(model: &Model) -> Status {
+    let span = model.period.span();
+
+    Status {
+        stance: current_stance(model).name.clone(),
+        posture: posture(model),
+        paused: model.timer_state == TimerState::Paused,
+        holds: model.holds.clone(),
+        awaiting: model
+            .awaiting
+            .as_ref()
+            .map(|awaiting| model.stances[awaiting.stance].name.clone()),
+        snoozed: model.snoozed_duration.is_some(),
+        elapsed_seconds: model.timer.elapsed().as_secs(),
+        duration_seconds: current_duration(model).as_secs(),
+        durations_seconds: model
+            .stances
+            .iter()
+            .map(|stance| (stance.name.clone(), stance.duration.as_secs()))
+            .collect(),
+        period_start: span.start,
+        period_paused_seconds: span.paused.as_secs(),
+        warning: model.status.as_ref().map(|warning| warning.text.clone()),
+        pomodoro: model.pomodoro.as_ref().map(|_| PomodoroStatus {
+            session: model.session,
+            elapsed_seconds: model.pomodoro_timer.elapsed().as_secs(),
+        }),
+        ramp_up: ramp_up_progress(model),
+    }
+}
+
+/// Progress of the ramp-up program run here, or by the daemon the TUI is attached to.
+fn ramp_up_progress(model: &Model) -> Option<ramp_up::Progress> {
+    match &model.ramp_up {
+        Some(program) => Some(program.progress(&model.stances[program.stance].name)),
+        None => model.daemon_ramp_up.clone(),
+    }
+}
+
+/// Updates the properties of the D-Bus service after the model changed.
+fn publish(model: &mut Model) {
+    let Some(bus) = &model.bus else {
+        return;
+    };
+
+    if let Err(err) = bus.publish(status(model)) {
+        model.status = Some(Warning::new(format!(
+            "Could not update the D-Bus service: {}",
+            err
+        )));
+    }
+}
+
+/// Tells the D-Bus service about the stance that just started, and moves the desk and runs the
+/// hook of its posture when that differs from `previous`.
+fn announce_stance(model: &mut Model, previous: State) {
+    let posture = posture(model);
+    if posture != previous {
+        if let Some(desk) = &mut model.desk {
+            desk.move_to(posture);
+        }
+
+        run_hook(model, HookEvent::started(posture));
+    }
+
+    let Some(bus) = &model.bus else {
+        return;
+    };
+
+    if let Err(err) = bus.stance_changed(&current_stance(model).name, current_duration(model)) {
+        model.status = Some(Warning::new(format!(
+            "Could not update the D-Bus service: {}",
+            err
+        )));
+    }
+}
+
+/// Runs the hook of `event` with the current state of the timer.
+fn run_hook(model: &Model, event: HookEvent) {
+    // The hooks of an attached daemon run on its side
+    if model.daemon.is_some() {
+        return;
+    }
+
+    let duration = current_duration(model).saturating_sub(model.timer.elapsed());
+    let end_time = (!is_paused(model))
+        .then(|| chrono::TimeDelta::from_std(duration).ok())
+        .flatten()
+        .map(|left| chrono::Local::now() + left);
+
+    model.hooks.run(
+        event,
+        &HookContext {
+            stance: current_stance(model).name.clone(),
+            posture: posture(model),
+            duration,
+            end_time,
+        },
+        &model.sender,
+    );
+}
+
+/// Mirrors the status of the daemon in the model displayed by the TUI.
+fn apply_status(model: &mut Model, status: Status) {
+    if let Some(position) = cycle_position(model, &status.stance) {
+        model.position = position;
+    }
+    model.timer_state = if status.paused {
+        TimerState::Paused
+    } else {
+        TimerState::InProgress
+    };
+    model.holds = status.holds;
+    let awaiting = status
+        .awaiting
+        .as_deref()
+        .and_then(|name| stance_index(model, name));
+    if model.awaiting.as_ref().map(|awaiting| awaiting.stance) != awaiting {
+        model.awaiting = awaiting.map(Awaiting::new);
+    }
+    for stance in &mut model.stances {
+        if let Some(seconds) = status.durations_seconds.get(&stance.name) {
+            stance.duration = Duration::from_secs(*seconds);
+        }
+    }
+    model.snoozed_duration = status
+        .snoozed
+        .then_some(Duration::from_secs(status.duration_seconds));
+    let period_changed = model.period.span().start != status.period_start;
+    model.period = Period::restore(
+        &status.stance,
+        status.posture,
+        status.period_start,
+        Duration::from_secs(status.period_paused_seconds),
+    );
+    sync_pause(model);
+    model
+        .timer
+        .set_elapsed(Duration::from_secs(status.elapsed_seconds));
+    if let Some(pomodoro) = status.pomodoro {
+        model.session = pomodoro.session;
+        model
+            .pomodoro_timer
+            .set_elapsed(Duration::from_secs(pomodoro.elapsed_seconds));
+    }
+    model.daemon_ramp_up = status.ramp_up;
+    // A new warning of the daemon is shown once, then expires like the local ones
+    if status.warning != model.daemon_warning {
+        if let Some(warning) = &status.warning {
+            model.status = Some(Warning::new(warning.clone()));
+        }
+        model.daemon_warning = status.warning;
+    }
+    // Periods are recorded by the daemon, whose history the standing goal is computed from
+    if period_changed && model.standing_goal.is_some() {
+        load_today(model);
+    }
+}
+
+/// Sends the reminder through every notifier, falling back to the terminal bell when none of
+/// them succeeded.
+fn notify(model: &mut Model, reminder: &Reminder) {
+    let reminder = Reminder {
+        period_start: Some(model.period.span().start),
+        ..reminder.clone()
+    };
+    let errors: Vec<String> = model
+        .notifiers
+        .iter_mut()
+        .filter_map(|notifier| notifier.notify(&reminder).err())
+        .map(|err| err.to_string())
+        .collect();
+
+    if errors.is_empty() {
+        return;
+    }
+
+    if errors.len() == model.notifiers.len() {
+        let _ = notification::ring_terminal_bell();
+    }
+    model.status = Some(Warning::new(format!(
+        "{} {} ({})",
+        reminder.title,
+        reminder.message,
+        errors.join(", ")
+    )));
+}
+
+/// Records the current period in the history and starts a period for the next stance of the
+/// cycle.
+fn change_stance(model: &mut Model, ended_by: EndReason) {
+    record_period(model, ended_by);
+
+    let previous = posture(model);
+    model.position = next_position(model);
+    start_period(model);
+    announce_stance(model, previous);
+}
+
+fn start_period(model: &mut Model) {
+    model.period = Period::new(&current_stance(model).name, posture(model));
+    if is_paused(model) {
+        model.period.pause();
+    }
+}
+
+/// Whether the timer is paused, by the user or by a hold.
+fn is_paused(model: &Model) -> bool {
+    model.timer_state == TimerState::Paused || !model.holds.is_empty()
+}
+
+/// Pauses or resumes the timer and the current period to match `is_paused`, running the pause
+/// or resume hook when that changes.
+fn sync_pause(model: &mut Model) {
+    let was_paused = model.timer.is_paused();
+
+    if is_paused(model) {
+        model.timer.pause();
+        model.pomodoro_timer.pause();
+        model.period.pause();
+    } else {
+        model.timer.resume();
+        model.pomodoro_timer.resume();
+        model.period.resume();
+    }
+
+    match (was_paused, model.timer.is_paused()) {
+        (false, true) => run_hook(model, HookEvent::Pause),
+        (true, false) => run_hook(model, HookEvent::Resume),
+        _ => {}
+    }
+}
+
+fn return_policy(model: &Model, hold: Hold) -> ReturnPolicy {
+    match hold {
+        Hold::Idle => model.idle_return,
+        Hold::Locked | Hold::Sleep => model.lock_return,
+        Hold::OffHours => model
+            .schedule
+            .as_ref()
+            .map_or(ReturnPolicy::Reset, |schedule| schedule.on_return),
+    }
+}
+
+fn current_stance(model: &Model) -> &Stance {
+    &model.stances[model.cycle[model.position]]
+}
+
+/// Whether the current stance counts as sitting or standing.
+fn posture(model: &Model) -> State {
+    current_stance(model).posture
+}
+
+fn next_position(model: &Model) -> usize {
+    (model.position + 1) % model.cycle.len()
+}
+
+fn stance_index(model: &Model, name: &str) -> Option<usize> {
+    model.stances.iter().position(|stance| stance.name == name)
+}
+
+/// Position in the cycle of the stance called `name`, preferring the current and next positions
+/// when the stance appears several times.
+fn cycle_position(model: &Model, name: &str) -> Option<usize> {
+    let index = stance_index(model, name)?;
+
+    [model.position, next_position(model)]
+        .into_iter()
+        .chain(0..model.cycle.len())
+        .find(|&position| model.cycle[position] == index)
+}
+
+/// Stance whose duration is edited by the selected settings block.
+fn selected_stance(model: &Model) -> Option<&Stance> {
+    match model.selected_widget_block {
+        WidgetBlock::Settings(index) => model.stances.get(index),
+        WidgetBlock::Timer => None,
+    }
+}
+
+/// Duration of the stance at `index` one step longer or shorter, within the bounds.
+fn stepped_duration(model: &Model, index: usize, increase: bool) -> Duration {
+    let duration = model.stances[index].duration;
+    let duration = if increase {
+        duration.saturating_add(model.step_duration)
+    } else {
+        duration.saturating_sub(model.step_duration)
+    };
+
+    duration.clamp(model.min_duration, model.max_duration)
+}
+
+/// Whether the current stance is a sitting one followed by a standing one, which should not
+/// start during a meeting.
+fn is_stand_transition(model: &Model) -> bool {
+    let next = &model.stances[model.cycle[next_position(model)]];
+
+    posture(model) == State::Sitting && next.posture == State::Standing
+}
+
+/// When a stance change moved around the meetings or to the next Pomodoro break happens, `None`
+/// when it is not moved or while paused.
+fn stance_change_time(model: &Model) -> Option<chrono::DateTime<chrono::Local>> {
+    if is_paused(model) {
+        return None;
+    }
+
+    let time_left = current_duration(model).saturating_sub(model.timer.elapsed());
+    let now = chrono::Local::now();
+    let end = now + chrono::TimeDelta::from_std(time_left).unwrap_or_default();
+
+    let rescheduled = model
+        .agenda
+        .as_ref()
+        .filter(|_| is_stand_transition(model))
+        .map(|agenda| agenda.reschedule(now, end));
+
+    match next_break(model) {
+        Some(next_break) => Some(rescheduled.unwrap_or(end).max(next_break)),
+        None => rescheduled,
+    }
+}
+
+/// When the current work session ends, if stance changes wait for the Pomodoro breaks.
+fn next_break(model: &Model) -> Option<chrono::DateTime<chrono::Local>> {
+    let pomodoro = model
+        .pomodoro
+        .as_ref()
+        .filter(|pomodoro| pomodoro.align_stance_changes)?;
+    if model.session.phase.is_break() {
+        return None;
+    }
+
+    let time_left = pomodoro.work.saturating_sub(model.pomodoro_timer.elapsed());
+
+    Some(chrono::Local::now() + chrono::TimeDelta::from_std(time_left).unwrap_or_default())
+}
+
+fn stance_change_due(model: &Model) -> bool {
+    // Due or not, the change waits for the end of the work session
+    if next_break(model).is_some() {
+        return false;
+    }
+
+    match stance_change_time(model) {
+        Some(time) => time <= chrono::Local::now(),
+        None => model.timer.elapsed() > current_duration(model),
+    }
+}
+
+/// Meeting in progress or coming next today, for the title of the timer block.
+fn upcoming_meeting(model: &Model) -> String {
+    let now = chrono::Local::now();
+    let Some(event) = model
+        .agenda
+        .as_ref()
+        .and_then(|agenda| agenda.upcoming(now))
+    else {
+        return String::new();
+    };
+
+    if event.start <= now {
+        format!(
+            " | {} until {}",
+            event.summary,
+            event.end.format(SHORT_TIME_FORMAT)
+        )
+    } else {
+        format!(
+            " | {} at {}",
+            event.summary,
+            event.start.format(SHORT_TIME_FORMAT)
+        )
+    }
+}
+
+/// Duration of the current period, shortened to the snooze duration after a snooze.
+fn current_duration(model: &Model) -> Duration {
+    model
+        .snoozed_duration
+        .unwrap_or(current_stance(model).duration)
+}
+
+fn record_period(model: &mut Model, ended_by: EndReason) {
+    let record = model.period.finish(ended_by);
+
+    if let Err(err) = model.history.append(&record) {
+        model.status = Some(Warning::new(format!(
+            "Could not write the session history: {}",
+            err
+        )));
+    }
+
+    model.records.push(record);
 }
 
 fn ratio_duration(duration: Duration, min: Duration, max: Duration) -> f64 {
@@ -421,6 +1943,8 @@ fn format_duration_hours_minutes_seconds(duration: Duration) -> String {
 }
 
 const LONG_TIME_FORMAT: &str = "%H:%M:%S";
+const SHORT_TIME_FORMAT: &str = "%H:%M";
+const DAY_TIME_FORMAT: &str = "%a %H:%M";
 
 fn format_time_after_duration(duration: Duration) -> String {
     let sleep_time = duration.as_secs();
@@ -435,9 +1959,53 @@ fn format_time_after_duration(duration: Duration) -> String {
     sleep_end_time.format(LONG_TIME_FORMAT).to_string()
 }
 
+/// Start of the working hours, with the day when it is not today.
+fn format_start(start: chrono::NaiveDateTime) -> String {
+    if start.date() == chrono::Local::now().date_naive() {
+        start.format(SHORT_TIME_FORMAT).to_string()
+    } else {
+        start.format(DAY_TIME_FORMAT).to_string()
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
+    use notification::NotifyError;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+    use test_dir::TestDir;
+
+    #[derive(Debug, Default)]
+    struct RecordingNotifier(Arc<Mutex<Vec<Reminder>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&mut self, reminder: &Reminder) -> Result<(), NotifyError> {
+            self.0.lock().unwrap().push(reminder.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct FailingNotifier;
+
+    impl Notifier for FailingNotifier {
+        fn notify(&mut self, _reminder: &Reminder) -> Result<(), NotifyError> {
+            Err(NotifyError::Io("failing", io::Error::other("unavailable")))
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingDesk(Arc<Mutex<Vec<State>>>);
+
+    impl DeskController for RecordingDesk {
+        fn move_to(&mut self, stance: State) {
+            self.0.lock().unwrap().push(stance);
+        }
+    }
 
     #[test]
     fn test_update_quit() {
@@ -451,45 +2019,322 @@ mod tests {
     #[test]
     fn test_update_increase_sitting() {
         let mut model = Model::default();
-        model.sitting_duration = Duration::from_secs(1800);
-        model.selected_widget_block = WidgetBlock::SittingSettings;
+        model.stances[0].duration = Duration::from_secs(1800);
+        model.selected_widget_block = WidgetBlock::Settings(0);
 
         update(&mut model, Message::Increase);
 
-        assert_eq!(model.sitting_duration, Duration::from_secs(2100));
+        assert_eq!(model.stances[0].duration, Duration::from_secs(2100));
     }
 
     #[test]
     fn test_update_increase_standing() {
         let mut model = Model::default();
-        model.standing_duration = Duration::from_secs(1800);
-        model.selected_widget_block = WidgetBlock::StandingSettings;
+        model.stances[1].duration = Duration::from_secs(1800);
+        model.selected_widget_block = WidgetBlock::Settings(1);
 
         update(&mut model, Message::Increase);
 
-        assert_eq!(model.standing_duration, Duration::from_secs(2100));
+        assert_eq!(model.stances[1].duration, Duration::from_secs(2100));
     }
 
     #[test]
     fn test_update_decrease_sitting() {
         let mut model = Model::default();
-        model.sitting_duration = Duration::from_secs(1800);
-        model.selected_widget_block = WidgetBlock::SittingSettings;
+        model.stances[0].duration = Duration::from_secs(1800);
+        model.selected_widget_block = WidgetBlock::Settings(0);
 
         update(&mut model, Message::Decrease);
 
-        assert_eq!(model.sitting_duration, Duration::from_secs(1500));
+        assert_eq!(model.stances[0].duration, Duration::from_secs(1500));
     }
 
     #[test]
     fn test_update_decrease_standing() {
         let mut model = Model::default();
-        model.standing_duration = Duration::from_secs(1800);
-        model.selected_widget_block = WidgetBlock::StandingSettings;
+        model.stances[1].duration = Duration::from_secs(1800);
+        model.selected_widget_block = WidgetBlock::Settings(1);
 
         update(&mut model, Message::Decrease);
 
-        assert_eq!(model.standing_duration, Duration::from_secs(1500));
+        assert_eq!(model.stances[1].duration, Duration::from_secs(1500));
+    }
+
+    #[test]
+    fn test_update_next() {
+        let mut model = Model::default();
+        model.position = 0;
+
+        update(&mut model, Message::Next);
+
+        assert_eq!(posture(&model), State::Standing);
+        assert_eq!(model.period.finish(EndReason::Skip).stance, State::Standing);
+    }
+
+    #[test]
+    fn test_update_next_follows_cycle() {
+        let config = Config {
+            stances: vec![
+                Stance::new("sitting", State::Sitting, Duration::from_secs(3600)),
+                Stance::new("standing", State::Standing, Duration::from_secs(900)),
+                Stance::new("walking", State::Standing, Duration::from_secs(1200)),
+            ],
+            cycle: ["sitting", "standing", "walking", "standing"]
+                .map(String::from)
+                .to_vec(),
+            start_stance: "walking".to_string(),
+            ..Config::default()
+        };
+        let mut model = Model::new(&config);
+        model.notifiers = Vec::new();
+
+        assert_eq!(current_stance(&model).name, "walking");
+        update(&mut model, Message::Next);
+        assert_eq!(current_stance(&model).name, "standing");
+        update(&mut model, Message::TimerFinished);
+        assert_eq!(current_stance(&model).name, "sitting");
+        assert_eq!(current_duration(&model), Duration::from_secs(3600));
+        update(&mut model, Message::Snooze);
+        assert_eq!(current_stance(&model).name, "standing");
+        assert_eq!(model.position, 3);
+
+        update(&mut model, Message::NavigateBackward);
+        assert_eq!(model.selected_widget_block, WidgetBlock::Settings(2));
+        update(&mut model, Message::Increase);
+        assert_eq!(model.stances[2].duration, Duration::from_secs(1500));
+    }
+
+    #[test]
+    fn test_update_next_while_paused() {
+        let mut model = Model::default();
+        update(&mut model, Message::Pause);
+
+        update(&mut model, Message::Next);
+
+        assert_eq!(model.timer_state, TimerState::Paused);
+        assert_eq!(posture(&model), State::Standing);
+    }
+
+    #[test]
+    fn test_update_next_keeps_record() {
+        let mut model = Model::default();
+
+        update(&mut model, Message::Next);
+
+        assert_eq!(model.records.len(), 1);
+        assert_eq!(model.records[0].stance, State::Sitting);
+        assert_eq!(model.records[0].ended_by, EndReason::Skip);
+    }
+
+    #[test]
+    fn test_update_timer_finished() {
+        let mut model = Model::default();
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        model.notifiers = vec![Box::new(RecordingNotifier(reminders.clone()))];
+
+        update(&mut model, Message::TimerFinished);
+
+        assert_eq!(posture(&model), State::Standing);
+        assert_eq!(model.records[0].ended_by, EndReason::Timer);
+        assert_eq!(model.running_state, RunningState::Running);
+
+        let reminders = reminders.lock().unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].posture, State::Standing);
+        assert_eq!(model.status, None);
+    }
+
+    #[test]
+    fn test_update_timer_finished_notifier_failure() {
+        let mut model = Model::default();
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        model.notifiers = vec![
+            Box::new(FailingNotifier),
+            Box::new(RecordingNotifier(reminders.clone())),
+        ];
+
+        update(&mut model, Message::TimerFinished);
+
+        assert_eq!(reminders.lock().unwrap().len(), 1);
+        assert!(model.status.unwrap().text.contains("unavailable"));
+    }
+
+    #[test]
+    fn test_update_dismiss_modal() {
+        let mut model = Model::default();
+        model
+            .modal
+            .show(Reminder::new(&model.stances[1], Duration::from_secs(60)));
+
+        update(&mut model, Message::DismissModal);
+
+        assert_eq!(model.modal.current(), None);
+    }
+
+    #[test]
+    fn test_update_snooze_after_timer_finished() {
+        let mut model = Model::default();
+        model.notifiers = Vec::new();
+        update(&mut model, Message::TimerFinished);
+
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(current_duration(&model), model.snooze_duration);
+        assert_eq!(model.snoozable, None);
+
+        update(&mut model, Message::TimerFinished);
+
+        assert_eq!(posture(&model), State::Standing);
+        assert_eq!(model.records[1].stance, State::Sitting);
+        assert_eq!(model.records[1].snoozes, 1);
+        assert_eq!(current_duration(&model), model.stances[1].duration);
+    }
+
+    #[test]
+    fn test_update_snooze_without_timer_finished() {
+        let mut model = Model::default();
+
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(model.snoozed_duration, None);
+    }
+
+    #[test]
+    fn test_update_snooze_too_late() {
+        let mut model = Model {
+            notifiers: Vec::new(),
+            ..Model::default()
+        };
+        update(&mut model, Message::TimerFinished);
+        model.timer.set_elapsed(model.snooze_duration);
+
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(posture(&model), State::Standing);
+        assert_eq!(model.snoozed_duration, None);
+        assert_eq!(model.period.snoozes(), 0);
+    }
+
+    #[test]
+    fn test_update_stale_reminder_action() {
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        let mut model = Model {
+            notifiers: vec![Box::new(RecordingNotifier(reminders.clone()))],
+            ..Model::default()
+        };
+        update(&mut model, Message::TimerFinished);
+        let period_start = reminders.lock().unwrap()[0].period_start;
+        assert_eq!(period_start, Some(model.period.span().start));
+
+        let stale = Some(period_start.unwrap() - chrono::TimeDelta::hours(1));
+        let action = Message::ReminderAction(notification::Action::Snooze, stale);
+        assert!(update(&mut model, action).is_none());
+
+        let action = Message::ReminderAction(notification::Action::Snooze, period_start);
+        assert!(matches!(update(&mut model, action), Some(Message::Snooze)));
+    }
+
+    #[test]
+    fn test_update_snooze_after_next() {
+        let mut model = Model::default();
+        model.notifiers = Vec::new();
+        update(&mut model, Message::TimerFinished);
+        update(&mut model, Message::Next);
+
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(model.snoozed_duration, None);
+    }
+
+    #[test]
+    fn test_update_timer_finished_awaiting_acknowledgement() {
+        let mut model = Model::default();
+        model.require_acknowledgement = true;
+        model.notifiers = Vec::new();
+
+        update(&mut model, Message::TimerFinished);
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(
+            model.awaiting.as_ref().map(|awaiting| awaiting.stance),
+            Some(1)
+        );
+        assert!(model.records.is_empty());
+        assert_eq!(handle_async(&model).map(|_| ()), None);
+    }
+
+    #[test]
+    fn test_update_acknowledge() {
+        let mut model = Model::default();
+        model.require_acknowledgement = true;
+        model.notifiers = Vec::new();
+        update(&mut model, Message::TimerFinished);
+
+        update(&mut model, Message::Acknowledge);
+
+        assert_eq!(posture(&model), State::Standing);
+        assert_eq!(model.awaiting, None);
+        assert_eq!(model.records.len(), 1);
+        assert_eq!(model.records[0].stance, State::Sitting);
+        assert!(model.timer.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_update_snooze_while_awaiting_acknowledgement() {
+        let mut model = Model::default();
+        model.require_acknowledgement = true;
+        model.notifiers = Vec::new();
+        update(&mut model, Message::TimerFinished);
+
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(model.awaiting, None);
+        assert_eq!(current_duration(&model), model.snooze_duration);
+        assert_eq!(model.period.snoozes(), 1);
+        assert_eq!(model.records.len(), 1);
+    }
+
+    #[test]
+    fn test_update_escalate() {
+        let mut model = Model::default();
+        model.require_acknowledgement = true;
+        model.escalation = Escalation {
+            interval: Duration::from_secs(60),
+            steps: vec![Step::Notify, Step::Flash],
+        };
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        model.notifiers = vec![Box::new(RecordingNotifier(reminders.clone()))];
+        update(&mut model, Message::TimerFinished);
+        assert!(handle_async(&model).is_none());
+
+        model.awaiting.as_mut().unwrap().reminded_at = Instant::now() - Duration::from_secs(61);
+        assert!(matches!(handle_async(&model), Some(Message::Escalate)));
+
+        update(&mut model, Message::Escalate);
+        update(&mut model, Message::Escalate);
+
+        assert_eq!(model.awaiting.as_ref().unwrap().level, 2);
+        assert_eq!(reminders.lock().unwrap().len(), 3);
+        assert!(is_flashing(&model));
+
+        update(&mut model, Message::Acknowledge);
+
+        assert!(!is_flashing(&model));
+    }
+
+    #[test]
+    fn test_update_toggle_statistics() {
+        let mut model = Model::default();
+
+        update(&mut model, Message::ToggleStatistics);
+        assert!(model.show_statistics);
+
+        update(&mut model, Message::ToggleStatistics);
+        assert!(!model.show_statistics);
     }
 
     #[test]
@@ -512,6 +2357,519 @@ mod tests {
         assert_eq!(model.timer_state, TimerState::InProgress);
     }
 
+    #[test]
+    fn test_update_idle_hold_and_release() {
+        let mut model = Model::default();
+
+        update(&mut model, Message::Hold(Hold::Idle));
+
+        assert!(is_paused(&model));
+        assert_eq!(model.timer_state, TimerState::InProgress);
+
+        update(&mut model, Message::Release(Hold::Idle));
+
+        assert!(!is_paused(&model));
+    }
+
+    #[test]
+    fn test_update_release_keeps_user_pause() {
+        let mut model = Model::default();
+        update(&mut model, Message::Pause);
+        update(&mut model, Message::Hold(Hold::Idle));
+
+        update(&mut model, Message::Release(Hold::Idle));
+
+        assert_eq!(model.timer_state, TimerState::Paused);
+        assert!(is_paused(&model));
+    }
+
+    #[test]
+    fn test_update_release_with_reset_policy() {
+        let mut model = Model::default();
+        model.idle_return = ReturnPolicy::Reset;
+        update(&mut model, Message::Hold(Hold::Idle));
+
+        let next = update(&mut model, Message::Release(Hold::Idle));
+
+        assert!(matches!(next, Some(Message::Reset)));
+        assert!(update(&mut model, Message::Release(Hold::Idle)).is_none());
+    }
+
+    #[test]
+    fn test_update_release_uses_policy_of_hold() {
+        let mut model = Model::default();
+        model.lock_return = ReturnPolicy::Reset;
+        update(&mut model, Message::Hold(Hold::Idle));
+        update(&mut model, Message::Hold(Hold::Sleep));
+
+        assert!(update(&mut model, Message::Release(Hold::Idle)).is_none());
+        assert!(is_paused(&model));
+        assert!(matches!(
+            update(&mut model, Message::Release(Hold::Sleep)),
+            Some(Message::Reset)
+        ));
+        assert!(!is_paused(&model));
+    }
+
+    #[test]
+    fn test_off_hours_hold() {
+        let mut model = Model::default();
+        model.schedule = Some(Schedule {
+            enabled: true,
+            days: Vec::new(),
+            ..Schedule::default()
+        });
+
+        let message = handle_async(&model).unwrap();
+        assert!(matches!(message, Message::Hold(Hold::OffHours)));
+        update(&mut model, message);
+
+        assert!(is_paused(&model));
+        assert!(handle_async(&model).is_none());
+        assert!(matches!(
+            update(&mut model, Message::Release(Hold::OffHours)),
+            Some(Message::Reset)
+        ));
+    }
+
+    #[test]
+    fn test_standing_goal_reached_once() {
+        let now = chrono::Local::now();
+        let mut model = Model::default();
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        model.notifiers = vec![Box::new(RecordingNotifier(reminders.clone()))];
+        model.standing_goal = Some(Duration::from_secs(60));
+
+        assert!(handle_async(&model).is_none());
+
+        model.records.push(Record {
+            start: now - chrono::TimeDelta::minutes(5),
+            end: now,
+            stance: State::Standing,
+            name: stance::STANDING.to_string(),
+            ended_by: EndReason::Timer,
+            paused_seconds: 0,
+            snoozes: 0,
+        });
+
+        let message = handle_async(&model).unwrap();
+        assert!(matches!(message, Message::GoalReached));
+        update(&mut model, message);
+
+        assert!(handle_async(&model).is_none());
+        let reminders = reminders.lock().unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].kind, notification::ReminderKind::Goal);
+    }
+
+    #[test]
+    fn test_achievements_include_today() {
+        let now = chrono::Local::now();
+        let mut model = Model::default();
+        model.standing_goal = Some(Duration::from_secs(60));
+        model.past_standing = BTreeMap::from([(
+            now.date_naive().pred_opt().unwrap(),
+            Duration::from_secs(120),
+        )]);
+
+        assert_eq!(achievements(&model).current_streak, Some(1));
+
+        model.records.push(Record {
+            start: now - chrono::TimeDelta::minutes(5),
+            end: now,
+            stance: State::Standing,
+            name: stance::STANDING.to_string(),
+            ended_by: EndReason::Timer,
+            paused_seconds: 0,
+            snoozes: 0,
+        });
+        let achievements = achievements(&model);
+
+        assert_eq!(achievements.current_streak, Some(2));
+        assert_eq!(achievements.longest_streak, Some(2));
+        assert_eq!(achievements.standing_seconds, 420);
+    }
+
+    #[test]
+    fn test_standing_goal_survives_restart() {
+        let dir = TestDir::new("goal");
+        let history = History::new(Some(dir.join(history::HISTORY_FILE_NAME)));
+        let period = Period::restore(
+            stance::STANDING,
+            State::Standing,
+            chrono::Local::now() - chrono::TimeDelta::minutes(5),
+            Duration::ZERO,
+        );
+        history.append(&period.finish(EndReason::Quit)).unwrap();
+
+        let mut model = Model::default();
+        model.standing_goal = Some(Duration::from_secs(60));
+        model.history = history;
+        load_today(&mut model);
+
+        assert_eq!(
+            model.goal_reached_on,
+            Some(chrono::Local::now().date_naive())
+        );
+        assert!(handle_async(&model).is_none());
+    }
+
+    #[test]
+    fn test_ramp_up_advances_on_new_day() {
+        let today = chrono::Local::now().date_naive();
+        let mut model = Model::default();
+        model.ramp_up = Some(Program {
+            stance: 1,
+            ramp_up: ramp_up::RampUp::default(),
+            state: ramp_up::RampState::new(today.pred_opt().unwrap()),
+            store: ramp_up::Store::default(),
+        });
+
+        let message = handle_async(&model).unwrap();
+        assert!(matches!(message, Message::AdvanceRampUp));
+        update(&mut model, message);
+
+        assert!(handle_async(&model).is_none());
+        assert_eq!(model.stances[1].duration, Duration::from_secs(900));
+        assert_eq!(model.ramp_up.unwrap().state.last_day, today);
+    }
+
+    #[test]
+    fn test_pomodoro_phase_finished() {
+        let mut model = Model::default();
+        let reminders = Arc::new(Mutex::new(Vec::new()));
+        model.notifiers = vec![Box::new(RecordingNotifier(reminders.clone()))];
+        model.pomodoro = Some(Pomodoro::default());
+        model.pomodoro_timer.set_elapsed(Duration::from_secs(1500));
+
+        let message = handle_async(&model).unwrap();
+        assert!(matches!(message, Message::PhaseFinished));
+        update(&mut model, message);
+
+        assert_eq!(model.session.phase, Phase::ShortBreak);
+        assert_eq!(model.session.completed, 1);
+        assert!(model.pomodoro_timer.elapsed() < Duration::from_secs(1));
+        assert_eq!(posture(&model), State::Sitting);
+
+        let reminders = reminders.lock().unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].kind, notification::ReminderKind::Pomodoro);
+    }
+
+    #[test]
+    fn test_pomodoro_phase_keeps_acknowledgement_modal() {
+        let mut model = Model {
+            require_acknowledgement: true,
+            pomodoro: Some(Pomodoro::default()),
+            ..Model::default()
+        };
+        model.notifiers = notification::notifiers(
+            &[notification::NotifierKind::Modal],
+            None,
+            &model.modal,
+            &model.sender,
+        );
+        update(&mut model, Message::TimerFinished);
+
+        update(&mut model, Message::PhaseFinished);
+        let modal = model.modal.current().unwrap();
+        assert_eq!(modal.kind, ReminderKind::StanceChange);
+        assert!(modal.awaiting_acknowledgement);
+
+        update(&mut model, Message::Acknowledge);
+        assert_eq!(model.modal.current().unwrap().kind, ReminderKind::Pomodoro);
+    }
+
+    #[test]
+    fn test_stance_change_waits_for_break() {
+        let mut model = Model::default();
+        model.notifiers = Vec::new();
+        model.pomodoro = Some(Pomodoro {
+            align_stance_changes: true,
+            ..Pomodoro::default()
+        });
+        model.timer.set_elapsed(Duration::from_secs(3700));
+
+        assert!(handle_async(&model).is_none());
+        assert!(stance_change_time(&model).is_some());
+
+        update(&mut model, Message::SkipPhase);
+
+        assert_eq!(model.session.phase, Phase::ShortBreak);
+        assert!(matches!(handle_async(&model), Some(Message::TimerFinished)));
+    }
+
+    #[test]
+    fn test_stance_change_moved_around_meeting() {
+        let now = chrono::Local::now();
+        let mut model = Model::default();
+        model.agenda = Some(Agenda::new(&calendar::CalendarAwareness::default()));
+        model.agenda.as_mut().unwrap().events = vec![Event {
+            summary: "Standup".to_string(),
+            start: now - chrono::TimeDelta::minutes(5),
+            end: now + chrono::TimeDelta::minutes(10),
+        }];
+        model.timer.set_elapsed(Duration::from_secs(3700));
+
+        assert!(handle_async(&model).is_none());
+        assert!(upcoming_meeting(&model).starts_with(" | Standup until "));
+
+        let mut model = Model::default();
+        model.agenda = Some(Agenda::new(&calendar::CalendarAwareness {
+            on_conflict: calendar::Conflict::Advance,
+            ..calendar::CalendarAwareness::default()
+        }));
+        model.agenda.as_mut().unwrap().events = vec![Event {
+            summary: "Review".to_string(),
+            start: now + chrono::TimeDelta::minutes(1),
+            end: now + chrono::TimeDelta::minutes(30),
+        }];
+        model.timer.set_elapsed(Duration::from_secs(3000));
+
+        assert!(matches!(handle_async(&model), Some(Message::TimerFinished)));
+    }
+
+    #[test]
+    fn test_update_error() {
+        let mut model = Model::default();
+
+        update(
+            &mut model,
+            Message::Error("Idle detection stopped".to_string()),
+        );
+
+        assert_eq!(
+            model.status.as_ref().map(|warning| warning.text.as_str()),
+            Some("Idle detection stopped")
+        );
+
+        model.status.as_mut().unwrap().shown_at -= WARNING_DURATION;
+        let message = handle_async(&model).unwrap();
+        assert!(matches!(message, Message::ClearWarning));
+        update(&mut model, message);
+
+        assert_eq!(model.status, None);
+    }
+
+    #[test]
+    fn test_update_moves_desk() {
+        let moves = Arc::new(Mutex::new(Vec::new()));
+        let mut model = Model::default();
+        model.notifiers = Vec::new();
+        model.desk = Some(Box::new(RecordingDesk(moves.clone())));
+
+        update(&mut model, Message::Next);
+        update(&mut model, Message::TimerFinished);
+        update(&mut model, Message::Snooze);
+
+        assert_eq!(
+            *moves.lock().unwrap(),
+            vec![State::Standing, State::Sitting, State::Standing]
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_desk_for_same_posture() {
+        let config = Config {
+            stances: vec![
+                Stance::new("sitting", State::Sitting, Duration::from_secs(3600)),
+                Stance::new("standing", State::Standing, Duration::from_secs(900)),
+                Stance::new("walking", State::Standing, Duration::from_secs(1200)),
+            ],
+            cycle: ["sitting", "standing", "walking"]
+                .map(String::from)
+                .to_vec(),
+            ..Config::default()
+        };
+        let moves = Arc::new(Mutex::new(Vec::new()));
+        let mut model = Model::new(&config);
+        model.notifiers = Vec::new();
+        model.desk = Some(Box::new(RecordingDesk(moves.clone())));
+        model.hooks.on_stand = Some("exit 1".to_string());
+
+        update(&mut model, Message::Next);
+        model.inbox.recv_timeout(Duration::from_secs(5)).unwrap();
+        update(&mut model, Message::Next);
+
+        assert_eq!(current_stance(&model).name, "walking");
+        assert_eq!(*moves.lock().unwrap(), vec![State::Standing]);
+        assert!(model.inbox.recv_timeout(Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_update_next_runs_hook() {
+        let mut model = Model::default();
+        model.hooks.on_stand = Some("test \"$GET_UP_STANCE\" = sitting".to_string());
+        model.hooks.on_pause = Some("exit 0".to_string());
+
+        update(&mut model, Message::Next);
+
+        let message = model.inbox.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(message, Message::Error(error) if error.starts_with("The on_stand hook")));
+    }
+
+    #[test]
+    fn test_update_control_pause() {
+        let mut model = Model::default();
+        let (sender, reply) = mpsc::channel();
+
+        update(&mut model, Message::Control(Request::Pause, sender));
+
+        let Ok(Reply::Status(status)) = reply.recv() else {
+            panic!("expected a status");
+        };
+        assert!(status.paused);
+        assert_eq!(model.timer_state, TimerState::Paused);
+    }
+
+    #[test]
+    fn test_control_set_duration() {
+        let mut model = Model::default();
+
+        let reply = control(
+            &mut model,
+            Request::SetDuration("standing".to_string(), Duration::from_secs(1200)),
+        );
+
+        assert!(
+            matches!(reply, Reply::Status(status) if status.durations_seconds["standing"] == 1200)
+        );
+        assert_eq!(model.stances[1].duration, Duration::from_secs(1200));
+    }
+
+    #[test]
+    fn test_control_toggle_and_increase() {
+        let mut model = Model::default();
+
+        control(&mut model, Request::Toggle);
+        assert_eq!(model.timer_state, TimerState::Paused);
+
+        control(&mut model, Request::Toggle);
+        assert_eq!(model.timer_state, TimerState::InProgress);
+
+        control(&mut model, Request::Increase("sitting".to_string()));
+        assert_eq!(model.stances[0].duration, Duration::from_secs(3900));
+    }
+
+    #[test]
+    fn test_control_set_duration_out_of_bounds() {
+        let mut model = Model::default();
+
+        let reply = control(
+            &mut model,
+            Request::SetDuration("sitting".to_string(), Duration::from_secs(60)),
+        );
+
+        assert!(matches!(reply, Reply::Error(_)));
+        assert_eq!(model.stances[0].duration, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_control_unknown_stance() {
+        let mut model = Model::default();
+
+        let reply = control(&mut model, Request::Increase("lying".to_string()));
+
+        assert!(matches!(reply, Reply::Error(error) if error.contains("sitting, standing")));
+    }
+
+    #[test]
+    fn test_apply_status() {
+        let mut daemon = Model::default();
+        daemon.notifiers = Vec::new();
+        update(&mut daemon, Message::TimerFinished);
+        update(&mut daemon, Message::Snooze);
+        update(&mut daemon, Message::Hold(Hold::Locked));
+        let mut model = Model::default();
+
+        apply_status(&mut model, status(&daemon));
+
+        assert_eq!(posture(&model), State::Sitting);
+        assert_eq!(current_duration(&model), daemon.snooze_duration);
+        assert_eq!(model.holds, vec![Hold::Locked]);
+        assert!(is_paused(&model));
+        assert_eq!(model.timer_state, TimerState::InProgress);
+    }
+
+    #[test]
+    fn test_apply_status_shows_daemon_warning_once() {
+        let mut daemon = Model::default();
+        update(
+            &mut daemon,
+            Message::Error("The on_stand hook failed".to_string()),
+        );
+        let mut model = Model::default();
+
+        apply_status(&mut model, status(&daemon));
+        assert!(model.status.is_some());
+
+        update(&mut model, Message::ClearWarning);
+        apply_status(&mut model, status(&daemon));
+        assert_eq!(model.status, None);
+
+        update(&mut daemon, Message::ClearWarning);
+        apply_status(&mut model, status(&daemon));
+        assert_eq!(model.daemon_warning, None);
+    }
+
+    #[test]
+    fn test_apply_status_mirrors_ramp_up() {
+        let daemon = Model {
+            ramp_up: Some(Program {
+                stance: 1,
+                ramp_up: ramp_up::RampUp::default(),
+                state: ramp_up::RampState::new(chrono::Local::now().date_naive()),
+                store: ramp_up::Store::default(),
+            }),
+            ..Model::default()
+        };
+        let mut model = Model::default();
+
+        apply_status(&mut model, status(&daemon));
+
+        let progress = ramp_up_progress(&model).unwrap();
+        assert_eq!(progress.stance, "standing");
+        assert_eq!(progress.describe(), "ramp-up 0/4 to 30m");
+        assert!(handle_async(&model).is_none());
+    }
+
+    #[test]
+    fn test_attached_tui_forwards_to_daemon() {
+        let dir = TestDir::new("attached");
+        let path = dir.join(daemon::SOCKET_FILE_NAME);
+        let (ready, started) = mpsc::channel();
+
+        let socket = path.clone();
+        std::thread::spawn(move || {
+            let mut daemon = Model::default();
+            daemon.notifiers = Vec::new();
+            daemon::listen(&socket, daemon.sender.clone()).unwrap();
+            ready.send(()).unwrap();
+
+            while let Ok(message) = daemon.inbox.recv() {
+                let mut current_message = Some(message);
+                while let Some(message) = current_message {
+                    current_message = update(&mut daemon, message);
+                }
+            }
+        });
+        started.recv().unwrap();
+
+        let mut model = Model::default();
+        model.daemon = Some(Client::connect(&path).unwrap());
+        model.selected_widget_block = WidgetBlock::Settings(1);
+
+        update(&mut model, Message::Pause);
+        update(&mut model, Message::Increase);
+        update(&mut model, Message::Next);
+
+        assert_eq!(model.timer_state, TimerState::Paused);
+        assert_eq!(model.stances[1].duration, Duration::from_secs(2100));
+        assert_eq!(posture(&model), State::Standing);
+        assert!(model.records.is_empty());
+        assert_eq!(model.status, None);
+    }
+
     #[test]
     fn test_update_navigate_forward_timer_block() {
         let mut model = Model::default();
@@ -519,23 +2877,23 @@ mod tests {
 
         update(&mut model, Message::NavigateForward);
 
-        assert_eq!(model.selected_widget_block, WidgetBlock::SittingSettings);
+        assert_eq!(model.selected_widget_block, WidgetBlock::Settings(0));
     }
 
     #[test]
     fn test_update_navigate_forward_sitting_settings_block() {
         let mut model = Model::default();
-        model.selected_widget_block = WidgetBlock::SittingSettings;
+        model.selected_widget_block = WidgetBlock::Settings(0);
 
         update(&mut model, Message::NavigateForward);
 
-        assert_eq!(model.selected_widget_block, WidgetBlock::StandingSettings);
+        assert_eq!(model.selected_widget_block, WidgetBlock::Settings(1));
     }
 
     #[test]
     fn test_update_navigate_forward_standing_settings_block() {
         let mut model = Model::default();
-        model.selected_widget_block = WidgetBlock::StandingSettings;
+        model.selected_widget_block = WidgetBlock::Settings(1);
 
         update(&mut model, Message::NavigateForward);
 
@@ -549,13 +2907,13 @@ mod tests {
 
         update(&mut model, Message::NavigateBackward);
 
-        assert_eq!(model.selected_widget_block, WidgetBlock::StandingSettings);
+        assert_eq!(model.selected_widget_block, WidgetBlock::Settings(1));
     }
 
     #[test]
     fn test_update_navigate_backward_sitting_settings_block() {
         let mut model = Model::default();
-        model.selected_widget_block = WidgetBlock::SittingSettings;
+        model.selected_widget_block = WidgetBlock::Settings(0);
 
         update(&mut model, Message::NavigateBackward);
 
@@ -565,10 +2923,10 @@ mod tests {
     #[test]
     fn test_update_navigate_backward_standing_settings_block() {
         let mut model = Model::default();
-        model.selected_widget_block = WidgetBlock::StandingSettings;
+        model.selected_widget_block = WidgetBlock::Settings(1);
 
         update(&mut model, Message::NavigateBackward);
 
-        assert_eq!(model.selected_widget_block, WidgetBlock::SittingSettings);
+        assert_eq!(model.selected_widget_block, WidgetBlock::Settings(0));
     }
 }
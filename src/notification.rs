@@ -1,46 +1,426 @@
-use std::time::Duration;
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, Write},
+    process::{Command, Stdio},
+    sync::{mpsc::Sender, Arc, Mutex},
+    thread,
+    time::Duration,
+};
 
+use chrono::{DateTime, Local};
 use notify_rust::{Notification, Urgency};
+use serde::Deserialize;
+
+use crate::{
+    duration::format_duration,
+    pomodoro::Phase,
+    stance::{self, Stance},
+    Message, State,
+};
+
+const SNOOZE_ACTION: &str = "snooze";
+const ACKNOWLEDGE_ACTION: &str = "acknowledge";
+
+const NOTIFICATIONS_SERVICE: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
 
 const UP_ICON: &str = "/usr/share/icons/HighContrast/32x32/actions/go-up.png";
 const DOWN_ICON: &str = "/usr/share/icons/HighContrast/32x32/actions/go-down.png";
 
 const UP_TITLE: &str = "Stand up!";
+const UP_ACTION: &str = "Stand up";
 const UP_MESSAGE: &str = "   ↑       ↑       ↑
   ↑↑↑     ↑↑↑     ↑↑↑
  ↑↑↑↑↑   ↑↑↑↑↑   ↑↑↑↑↑
 ↑↑↑↑↑↑↑ ↑↑↑↑↑↑↑ ↑↑↑↑↑↑↑";
 
 const DOWN_TITLE: &str = "Sit down!";
+const DOWN_ACTION: &str = "Sit down";
 const DOWN_MESSAGE: &str = "↓↓↓↓↓↓↓ ↓↓↓↓↓↓↓ ↓↓↓↓↓↓↓
   ↓↓↓↓↓   ↓↓↓↓↓   ↓↓↓↓↓
    ↓↓↓     ↓↓↓     ↓↓↓
     ↓       ↓       ↓";
 
-pub fn send_stand_notification(duration: Duration) {
-    let stand_up_end_time = format_time_after_duration(duration);
-    let message = format!("Stand up until {} \n{}", stand_up_end_time, UP_MESSAGE);
+const GOAL_TITLE: &str = "Standing goal reached!";
+const GOAL_MESSAGE: &str = r" \o/     \o/     \o/
+  |       |       |
+ / \     / \     / \";
+
+const WORK_TITLE: &str = "Back to work!";
+const SHORT_BREAK_TITLE: &str = "Take a short break!";
+const LONG_BREAK_TITLE: &str = "Take a long break!";
+
+/// Notification backends that can be enabled in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifierKind {
+    Desktop,
+    Bell,
+    Modal,
+    Command,
+}
+
+impl NotifierKind {
+    /// Whether the backend shows the reminders in the terminal of the TUI.
+    pub fn needs_terminal(self) -> bool {
+        matches!(self, NotifierKind::Bell | NotifierKind::Modal)
+    }
+}
+
+/// What a reminder announces, which decides its actions and artwork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    /// A stance change, which can be snoozed
+    StanceChange,
+    /// The standing goal of the day was reached
+    Goal,
+    /// A phase of the Pomodoro cycle started
+    Pomodoro,
+}
+
+impl ReminderKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ReminderKind::StanceChange => "stance",
+            ReminderKind::Goal => "goal",
+            ReminderKind::Pomodoro => "pomodoro",
+        }
+    }
+}
+
+/// Button of a notification acting on the stance change it announced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Snooze,
+    Acknowledge,
+}
+
+/// A stance change, or another event of the timer, to announce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// Name of the stance to switch to, of the current stance for Pomodoro reminders, or
+    /// `standing` for the goal.
+    pub stance: String,
+    pub posture: State,
+    pub title: String,
+    pub message: String,
+    pub icon: Option<String>,
+    /// The stance timer only starts once the change is confirmed.
+    pub awaiting_acknowledgement: bool,
+    pub kind: ReminderKind,
+    /// Start of the period the reminder was raised in, the only one its actions apply to.
+    pub period_start: Option<DateTime<Local>>,
+}
+
+impl Reminder {
+    /// Reminder to switch to `stance` for `duration`.
+    pub fn new(stance: &Stance, duration: Duration) -> Self {
+        let end_time = format_time_after_duration(duration);
+
+        Self {
+            message: format!("{} until {}", action(stance), end_time),
+            ..Self::announcing(stance, false)
+        }
+    }
+
+    /// Reminder to switch to `stance`, whose timer of `duration` starts once confirmed.
+    pub fn awaiting_acknowledgement(stance: &Stance, duration: Duration) -> Self {
+        Self {
+            message: format!(
+                "{} for {}, confirm once {}",
+                action(stance),
+                format_duration(duration),
+                stance.name
+            ),
+            ..Self::announcing(stance, true)
+        }
+    }
+
+    /// Congratulation for standing `goal` today.
+    pub fn goal_reached(goal: Duration) -> Self {
+        Self {
+            stance: stance::STANDING.to_string(),
+            posture: State::Standing,
+            title: GOAL_TITLE.to_string(),
+            message: format!("You stood for {} today, well done!", format_duration(goal)),
+            icon: None,
+            awaiting_acknowledgement: false,
+            kind: ReminderKind::Goal,
+            period_start: None,
+        }
+    }
+
+    /// Start of `phase` of the Pomodoro cycle, lasting `duration`, while in `stance`.
+    pub fn phase_started(stance: &Stance, phase: Phase, duration: Duration) -> Self {
+        let title = match phase {
+            Phase::Work => WORK_TITLE,
+            Phase::ShortBreak => SHORT_BREAK_TITLE,
+            Phase::LongBreak => LONG_BREAK_TITLE,
+        };
+
+        Self {
+            title: title.to_string(),
+            message: format!(
+                "{} until {}",
+                phase.label(),
+                format_time_after_duration(duration)
+            ),
+            icon: None,
+            kind: ReminderKind::Pomodoro,
+            ..Self::announcing(stance, false)
+        }
+    }
 
-    Notification::new()
-        .body(message.as_str())
-        .icon(UP_ICON)
-        .summary(UP_TITLE)
-        .urgency(Urgency::Critical)
-        .show()
-        .unwrap();
+    fn announcing(stance: &Stance, awaiting_acknowledgement: bool) -> Self {
+        let title = stance.title.clone().unwrap_or_else(|| {
+            match stance.posture {
+                State::Standing => UP_TITLE,
+                State::Sitting => DOWN_TITLE,
+            }
+            .to_string()
+        });
+
+        Self {
+            stance: stance.name.clone(),
+            posture: stance.posture,
+            title,
+            message: String::new(),
+            icon: stance.icon.clone(),
+            awaiting_acknowledgement,
+            kind: ReminderKind::StanceChange,
+            period_start: None,
+        }
+    }
+}
+
+/// What the user is asked to do, such as "Stand up".
+fn action(stance: &Stance) -> &str {
+    stance.message.as_deref().unwrap_or(match stance.posture {
+        State::Standing => UP_ACTION,
+        State::Sitting => DOWN_ACTION,
+    })
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Desktop(notify_rust::error::Error),
+    Io(&'static str, io::Error),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::Desktop(err) => {
+                write!(f, "could not show the desktop notification: {}", err)
+            }
+            NotifyError::Io(backend, err) => write!(f, "{} notifier failed: {}", backend, err),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+pub trait Notifier: fmt::Debug {
+    fn notify(&mut self, reminder: &Reminder) -> Result<(), NotifyError>;
+}
+
+/// Builds the notifiers enabled in the configuration, in order.
+pub fn notifiers(
+    kinds: &[NotifierKind],
+    command: Option<&str>,
+    modal: &Modal,
+    sender: &Sender<Message>,
+) -> Vec<Box<dyn Notifier>> {
+    kinds
+        .iter()
+        .map(|kind| -> Box<dyn Notifier> {
+            match kind {
+                NotifierKind::Desktop => Box::new(DesktopNotifier {
+                    sender: sender.clone(),
+                    shown: None,
+                }),
+                NotifierKind::Bell => Box::new(BellNotifier),
+                NotifierKind::Modal => Box::new(ModalNotifier(modal.clone())),
+                NotifierKind::Command => Box::new(CommandNotifier {
+                    command: command.unwrap_or_default().to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Notification shown by the desktop notification daemon, with a button to snooze the change.
+#[derive(Debug)]
+pub struct DesktopNotifier {
+    sender: Sender<Message>,
+    /// Id of the last stance change notification, closed when the next one is shown.
+    shown: Option<u32>,
 }
 
-pub fn send_sit_notification(duration: Duration) {
-    let sit_down_end_time = format_time_after_duration(duration);
-    let message = format!("Sit down until {} \n {}", sit_down_end_time, DOWN_MESSAGE);
+impl Notifier for DesktopNotifier {
+    fn notify(&mut self, reminder: &Reminder) -> Result<(), NotifyError> {
+        let (icon, art) = match reminder.posture {
+            State::Standing => (UP_ICON, UP_MESSAGE),
+            State::Sitting => (DOWN_ICON, DOWN_MESSAGE),
+        };
+        let icon = reminder.icon.as_deref().unwrap_or(icon);
+        let message = match reminder.kind {
+            ReminderKind::StanceChange => format!("{} \n{}", reminder.message, art),
+            ReminderKind::Goal => format!("{} \n{}", reminder.message, GOAL_MESSAGE),
+            ReminderKind::Pomodoro => reminder.message.clone(),
+        };
+
+        let mut notification = Notification::new();
+        notification
+            .body(message.as_str())
+            .icon(icon)
+            .summary(&reminder.title)
+            .urgency(if reminder.kind == ReminderKind::StanceChange {
+                Urgency::Critical
+            } else {
+                Urgency::Normal
+            });
+        if reminder.kind != ReminderKind::StanceChange {
+            notification.show().map_err(NotifyError::Desktop)?;
+            return Ok(());
+        }
+        if reminder.awaiting_acknowledgement {
+            notification.action(ACKNOWLEDGE_ACTION, "Done");
+        }
+        notification.action(SNOOZE_ACTION, "Snooze");
+
+        // Also ends the thread waiting for an action on the previous notification
+        if let Some(id) = self.shown.take() {
+            let _ = close_notification(id);
+        }
+        let handle = notification.show().map_err(NotifyError::Desktop)?;
+        self.shown = Some(handle.id());
+
+        let sender = self.sender.clone();
+        let period_start = reminder.period_start;
+        thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                let action = match action {
+                    SNOOZE_ACTION => Action::Snooze,
+                    ACKNOWLEDGE_ACTION => Action::Acknowledge,
+                    _ => return,
+                };
+                let _ = sender.send(Message::ReminderAction(action, period_start));
+            })
+        });
 
-    Notification::new()
-        .body(message.as_str())
-        .icon(DOWN_ICON)
-        .summary(DOWN_TITLE)
-        .urgency(Urgency::Critical)
-        .show()
-        .unwrap();
+        Ok(())
+    }
+}
+
+/// Closes the notification `id` if it is still shown.
+fn close_notification(id: u32) -> zbus::Result<()> {
+    zbus::blocking::Connection::session()?.call_method(
+        Some(NOTIFICATIONS_SERVICE),
+        NOTIFICATIONS_PATH,
+        Some(NOTIFICATIONS_SERVICE),
+        "CloseNotification",
+        &id,
+    )?;
+
+    Ok(())
+}
+
+/// Rings the terminal bell.
+#[derive(Debug)]
+pub struct BellNotifier;
+
+impl Notifier for BellNotifier {
+    fn notify(&mut self, _reminder: &Reminder) -> Result<(), NotifyError> {
+        ring_terminal_bell().map_err(|err| NotifyError::Io("bell", err))
+    }
+}
+
+/// Reminders displayed over the TUI one after the other, each until it is dismissed.
+#[derive(Debug, Clone, Default)]
+pub struct Modal(Arc<Mutex<VecDeque<Reminder>>>);
+
+impl Modal {
+    pub fn current(&self) -> Option<Reminder> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|reminders| reminders.front().cloned())
+    }
+
+    /// Queues `reminder`, replacing the pending one of the same kind.
+    pub fn show(&self, reminder: Reminder) {
+        if let Ok(mut reminders) = self.0.lock() {
+            match reminders
+                .iter_mut()
+                .find(|pending| pending.kind == reminder.kind)
+            {
+                Some(pending) => *pending = reminder,
+                None => reminders.push_back(reminder),
+            }
+        }
+    }
+
+    /// Dismisses the reminder on display, showing the next one.
+    pub fn dismiss(&self) {
+        if let Ok(mut reminders) = self.0.lock() {
+            reminders.pop_front();
+        }
+    }
+
+    /// Dismisses the pending reminder of `kind`, once what it announced was handled.
+    pub fn withdraw(&self, kind: ReminderKind) {
+        if let Ok(mut reminders) = self.0.lock() {
+            reminders.retain(|reminder| reminder.kind != kind);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ModalNotifier(Modal);
+
+impl Notifier for ModalNotifier {
+    fn notify(&mut self, reminder: &Reminder) -> Result<(), NotifyError> {
+        self.0.show(reminder.clone());
+
+        Ok(())
+    }
+}
+
+/// Runs a shell command with the reminder in its environment.
+#[derive(Debug)]
+pub struct CommandNotifier {
+    command: String,
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&mut self, reminder: &Reminder) -> Result<(), NotifyError> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("GET_UP_KIND", reminder.kind.name())
+            .env("GET_UP_STANCE", &reminder.stance)
+            .env("GET_UP_POSTURE", reminder.posture.name())
+            .env("GET_UP_TITLE", &reminder.title)
+            .env("GET_UP_MESSAGE", &reminder.message)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| NotifyError::Io("command", err))?;
+
+        thread::spawn(move || child.wait());
+
+        Ok(())
+    }
+}
+
+/// Rings the terminal bell, also used as an alert when every notifier failed.
+pub fn ring_terminal_bell() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x07")?;
+    stdout.flush()
 }
 
 const LONG_TIME_FORMAT: &str = "%H:%M:%S";
@@ -57,3 +437,119 @@ fn format_time_after_duration(duration: Duration) -> String {
 
     sleep_end_time.format(LONG_TIME_FORMAT).to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sitting() -> Stance {
+        Stance::new(stance::SITTING, State::Sitting, Duration::from_secs(3600))
+    }
+
+    fn standing() -> Stance {
+        Stance::new(stance::STANDING, State::Standing, Duration::from_secs(1800))
+    }
+
+    #[test]
+    fn test_reminder_new() {
+        let reminder = Reminder::new(&standing(), Duration::from_secs(1800));
+
+        assert_eq!(reminder.title, UP_TITLE);
+        assert!(reminder.message.starts_with("Stand up until "));
+    }
+
+    #[test]
+    fn test_reminder_custom_stance() {
+        let stance = Stance {
+            title: Some("Walk!".to_string()),
+            message: Some("Start the treadmill".to_string()),
+            icon: Some("walk.png".to_string()),
+            ..Stance::new("walking", State::Standing, Duration::from_secs(600))
+        };
+
+        let reminder = Reminder::awaiting_acknowledgement(&stance, stance.duration);
+
+        assert_eq!(reminder.stance, "walking");
+        assert_eq!(reminder.title, "Walk!");
+        assert_eq!(
+            reminder.message,
+            "Start the treadmill for 10m, confirm once walking"
+        );
+        assert_eq!(reminder.icon.as_deref(), Some("walk.png"));
+    }
+
+    #[test]
+    fn test_reminder_awaiting_acknowledgement() {
+        let reminder = Reminder::awaiting_acknowledgement(&standing(), Duration::from_secs(1800));
+
+        assert!(reminder.awaiting_acknowledgement);
+        assert_eq!(reminder.message, "Stand up for 30m, confirm once standing");
+    }
+
+    #[test]
+    fn test_reminder_goal_reached() {
+        let reminder = Reminder::goal_reached(Duration::from_secs(7200));
+
+        assert_eq!(reminder.kind, ReminderKind::Goal);
+        assert_eq!(reminder.posture, State::Standing);
+        assert!(!reminder.awaiting_acknowledgement);
+        assert_eq!(reminder.title, GOAL_TITLE);
+        assert_eq!(reminder.message, "You stood for 2h today, well done!");
+    }
+
+    #[test]
+    fn test_reminder_phase_started() {
+        let reminder =
+            Reminder::phase_started(&sitting(), Phase::ShortBreak, Duration::from_secs(300));
+
+        assert_eq!(reminder.kind, ReminderKind::Pomodoro);
+        assert_eq!(reminder.title, SHORT_BREAK_TITLE);
+        assert!(reminder.message.starts_with("Short break until "));
+    }
+
+    #[test]
+    fn test_modal_notifier() {
+        let modal = Modal::default();
+        let (sender, _) = std::sync::mpsc::channel();
+        let mut notifiers = notifiers(&[NotifierKind::Modal], None, &modal, &sender);
+        let reminder = Reminder::new(&sitting(), Duration::from_secs(3600));
+
+        notifiers[0].notify(&reminder).unwrap();
+        assert_eq!(modal.current(), Some(reminder));
+
+        modal.dismiss();
+        assert_eq!(modal.current(), None);
+    }
+
+    #[test]
+    fn test_modal_queues_reminders() {
+        let modal = Modal::default();
+        let stance_change =
+            Reminder::awaiting_acknowledgement(&standing(), Duration::from_secs(60));
+        let short_break =
+            Reminder::phase_started(&sitting(), Phase::ShortBreak, Duration::from_secs(300));
+        let work = Reminder::phase_started(&sitting(), Phase::Work, Duration::from_secs(1500));
+
+        modal.show(stance_change.clone());
+        modal.show(short_break);
+        modal.show(work.clone());
+        assert_eq!(modal.current(), Some(stance_change));
+
+        modal.withdraw(ReminderKind::StanceChange);
+        assert_eq!(modal.current(), Some(work));
+
+        modal.dismiss();
+        assert_eq!(modal.current(), None);
+    }
+
+    #[test]
+    fn test_command_notifier() {
+        let mut notifier = CommandNotifier {
+            command: "true".to_string(),
+        };
+
+        assert!(notifier
+            .notify(&Reminder::new(&sitting(), Duration::from_secs(60)))
+            .is_ok());
+    }
+}
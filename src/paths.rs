@@ -0,0 +1,30 @@
+use std::{env, path::PathBuf};
+
+const APP_DIRECTORY: &str = "get-up";
+
+/// `$XDG_CONFIG_HOME/get-up`, falling back to `~/.config/get-up`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_DATA_HOME/get-up`, falling back to `~/.local/share/get-up`.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_RUNTIME_DIR/get-up`, falling back to the data directory.
+pub fn runtime_dir() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .map(|path| path.join(APP_DIRECTORY))
+        .or_else(data_dir)
+}
+
+fn xdg_dir(variable: &str, home_fallback: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))
+        .map(|path| path.join(APP_DIRECTORY))
+}
@@ -20,6 +20,10 @@ impl Timer {
     }
 
     pub fn pause(&mut self) {
+        if self.state == State::Paused {
+            return;
+        }
+
         self.accumulated_time = self
             .accumulated_time
             .saturating_add(self.start_time.elapsed());
@@ -27,10 +31,24 @@ impl Timer {
     }
 
     pub fn resume(&mut self) {
+        if self.state == State::InProgress {
+            return;
+        }
+
         self.start_time = Instant::now();
         self.state = State::InProgress;
     }
 
+    /// Sets the elapsed time, keeping the timer paused or running.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.start_time = Instant::now();
+        self.accumulated_time = elapsed;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state == State::Paused
+    }
+
     pub fn elapsed(&self) -> Duration {
         match self.state {
             State::InProgress => self
@@ -62,4 +80,40 @@ mod tests {
         assert!(timer.elapsed().as_millis() < 100);
         assert_eq!(timer.state, State::InProgress);
     }
+
+    #[test]
+    fn test_set_elapsed_while_paused() {
+        let mut timer = Timer::default();
+        timer.pause();
+
+        timer.set_elapsed(Duration::from_secs(90));
+
+        assert_eq!(timer.elapsed().as_secs(), 90);
+        assert_eq!(timer.state, State::Paused);
+    }
+
+    #[test]
+    fn test_pause_twice() {
+        let mut timer = Timer {
+            start_time: Instant::now() - Duration::from_secs(10),
+            ..Timer::default()
+        };
+
+        timer.pause();
+        timer.pause();
+
+        assert_eq!(timer.elapsed().as_secs(), 10);
+    }
+
+    #[test]
+    fn test_resume_while_running() {
+        let mut timer = Timer {
+            start_time: Instant::now() - Duration::from_secs(10),
+            ..Timer::default()
+        };
+
+        timer.resume();
+
+        assert_eq!(timer.elapsed().as_secs(), 10);
+    }
 }
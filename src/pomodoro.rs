@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::duration;
+
+const DEFAULT_WORK: Duration = Duration::from_secs(1500);
+const DEFAULT_SHORT_BREAK: Duration = Duration::from_secs(300);
+const DEFAULT_LONG_BREAK: Duration = Duration::from_secs(900);
+const DEFAULT_LONG_BREAK_EVERY: u32 = 4;
+
+/// Work and break cycle running alongside the stance timer.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Pomodoro {
+    pub enabled: bool,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub work: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub short_break: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub long_break: Duration,
+    /// Number of work sessions before a long break.
+    pub long_break_every: u32,
+    /// Defers the stance changes due during a work session to the next break.
+    pub align_stance_changes: bool,
+}
+
+impl Default for Pomodoro {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            work: DEFAULT_WORK,
+            short_break: DEFAULT_SHORT_BREAK,
+            long_break: DEFAULT_LONG_BREAK,
+            long_break_every: DEFAULT_LONG_BREAK_EVERY,
+            align_stance_changes: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    #[default]
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::ShortBreak => "Short break",
+            Phase::LongBreak => "Long break",
+        }
+    }
+
+    pub fn is_break(self) -> bool {
+        self != Phase::Work
+    }
+}
+
+/// Position in the Pomodoro cycle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub phase: Phase,
+    /// Work sessions completed since the cycle started.
+    pub completed: u32,
+}
+
+impl Pomodoro {
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, duration) in [
+            ("work", self.work),
+            ("short_break", self.short_break),
+            ("long_break", self.long_break),
+        ] {
+            if duration.is_zero() {
+                return Err(format!("{} must be greater than zero", key));
+            }
+        }
+
+        if self.long_break_every == 0 {
+            return Err("long_break_every must be greater than zero".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn duration(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Work => self.work,
+            Phase::ShortBreak => self.short_break,
+            Phase::LongBreak => self.long_break,
+        }
+    }
+
+    /// Session following `session`, a long break coming after every `long_break_every` work
+    /// sessions.
+    pub fn next(&self, session: Session) -> Session {
+        match session.phase {
+            Phase::Work => {
+                let completed = session.completed + 1;
+                let phase = if completed.is_multiple_of(self.long_break_every) {
+                    Phase::LongBreak
+                } else {
+                    Phase::ShortBreak
+                };
+
+                Session { phase, completed }
+            }
+            Phase::ShortBreak | Phase::LongBreak => Session {
+                phase: Phase::Work,
+                ..session
+            },
+        }
+    }
+
+    /// Number of the work session within its set, from 1 to `long_break_every`.
+    pub fn set_position(&self, session: Session) -> u32 {
+        session.completed % self.long_break_every + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pomodoro() {
+        let pomodoro: Pomodoro = toml::from_str(
+            r#"
+            enabled = true
+            work = "50m"
+            long_break_every = 3
+            align_stance_changes = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(pomodoro.work, Duration::from_secs(3000));
+        assert_eq!(pomodoro.short_break, DEFAULT_SHORT_BREAK);
+        assert!(pomodoro.align_stance_changes);
+        assert!(pomodoro.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_pomodoro() {
+        let pomodoro = Pomodoro {
+            long_break_every: 0,
+            ..Pomodoro::default()
+        };
+
+        assert!(pomodoro.validate().is_err());
+    }
+
+    #[test]
+    fn test_next_session() {
+        let pomodoro = Pomodoro {
+            long_break_every: 2,
+            ..Pomodoro::default()
+        };
+
+        let phases: Vec<Phase> = std::iter::successors(Some(Session::default()), |session| {
+            Some(pomodoro.next(*session))
+        })
+        .take(6)
+        .map(|session| session.phase)
+        .collect();
+
+        assert_eq!(
+            phases,
+            vec![
+                Phase::Work,
+                Phase::ShortBreak,
+                Phase::Work,
+                Phase::LongBreak,
+                Phase::Work,
+                Phase::ShortBreak
+            ]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration::{self, format_duration},
+    paths, stance,
+};
+
+pub const RAMP_UP_FILE_NAME: &str = "ramp-up.json";
+
+const DEFAULT_START: Duration = Duration::from_secs(600);
+const DEFAULT_STEP: Duration = Duration::from_secs(300);
+const DEFAULT_TARGET: Duration = Duration::from_secs(1800);
+
+/// Lengthens the duration of a stance by a step every day or week, from `start` up to `target`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RampUp {
+    pub enabled: bool,
+    pub stance: String,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub start: Duration,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub step: Duration,
+    pub every: Interval,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub target: Duration,
+}
+
+impl Default for RampUp {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stance: stance::STANDING.to_string(),
+            start: DEFAULT_START,
+            step: DEFAULT_STEP,
+            every: Interval::Day,
+            target: DEFAULT_TARGET,
+        }
+    }
+}
+
+/// How often the program takes a step, counting only the days get-up runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interval {
+    #[default]
+    Day,
+    Week,
+}
+
+impl RampUp {
+    pub fn validate(&self, min: Duration, max: Duration) -> Result<(), String> {
+        if self.step.is_zero() {
+            return Err("step must be greater than zero".to_string());
+        }
+
+        if self.start > self.target {
+            return Err(format!(
+                "start ({}) must not be greater than target ({})",
+                format_duration(self.start),
+                format_duration(self.target)
+            ));
+        }
+
+        if self.start < min || self.target > max {
+            return Err(format!(
+                "start and target must be between min_duration ({}) and max_duration ({})",
+                format_duration(min),
+                format_duration(max)
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn duration(&self, steps: u32) -> Duration {
+        self.start
+            .saturating_add(self.step.saturating_mul(steps))
+            .min(self.target)
+    }
+
+    pub fn steps(&self) -> u32 {
+        let gap = self.target.saturating_sub(self.start).as_secs();
+
+        gap.div_ceil(self.step.as_secs().max(1))
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// Progress of the program, saved between launches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RampState {
+    pub started_on: NaiveDate,
+    pub last_day: NaiveDate,
+    pub steps: u32,
+}
+
+impl RampState {
+    pub fn new(today: NaiveDate) -> Self {
+        Self {
+            started_on: today,
+            last_day: today,
+            steps: 0,
+        }
+    }
+
+    /// State on `today`, one step further when it starts a new day or week of use.
+    pub fn advance(self, today: NaiveDate, every: Interval) -> Self {
+        if today <= self.last_day {
+            return self;
+        }
+
+        let new_interval = match every {
+            Interval::Day => true,
+            Interval::Week => today.iso_week() != self.last_day.iso_week(),
+        };
+
+        Self {
+            last_day: today,
+            steps: self.steps + u32::from(new_interval),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Store {
+    path: Option<PathBuf>,
+}
+
+impl Store {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    /// Reads the state, `None` before the program started.
+    pub fn load(&self) -> io::Result<Option<RampState>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        serde_json::from_str(&contents).map(Some).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), err),
+            )
+        })
+    }
+
+    pub fn save(&self, state: &RampState) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, serde_json::to_string(state)?)
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(RAMP_UP_FILE_NAME))
+}
+
+/// The program in progress for the stance at index `stance`.
+#[derive(Debug)]
+pub struct Program {
+    pub stance: usize,
+    pub ramp_up: RampUp,
+    pub state: RampState,
+    pub store: Store,
+}
+
+impl Program {
+    pub fn start(
+        stance: usize,
+        ramp_up: RampUp,
+        store: Store,
+        today: NaiveDate,
+    ) -> io::Result<Self> {
+        let state = match store.load()? {
+            Some(state) => state.advance(today, ramp_up.every),
+            None => RampState::new(today),
+        };
+        store.save(&state)?;
+
+        Ok(Self {
+            stance,
+            ramp_up,
+            state,
+            store,
+        })
+    }
+
+    /// Advances the program to `today`, returning whether that took a step.
+    pub fn advance(&mut self, today: NaiveDate) -> io::Result<bool> {
+        let state = self.state.advance(today, self.ramp_up.every);
+        if state == self.state {
+            return Ok(false);
+        }
+
+        let stepped = state.steps != self.state.steps;
+        self.state = state;
+        self.store.save(&self.state)?;
+
+        Ok(stepped)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.ramp_up.duration(self.state.steps)
+    }
+
+    /// `stance` is the name of the stance at index `self.stance`.
+    pub fn progress(&self, stance: &str) -> Progress {
+        Progress {
+            stance: stance.to_string(),
+            steps: self.state.steps,
+            total_steps: self.ramp_up.steps(),
+            target_seconds: self.ramp_up.target.as_secs(),
+        }
+    }
+}
+
+/// Progress of a program, also sent by the daemon to the TUI attached to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub stance: String,
+    pub steps: u32,
+    pub total_steps: u32,
+    pub target_seconds: u64,
+}
+
+impl Progress {
+    /// Progress shown in the settings block, such as "ramp-up 2/4 to 30m".
+    pub fn describe(&self) -> String {
+        if self.steps >= self.total_steps {
+            "ramp-up complete".to_string()
+        } else {
+            format!(
+                "ramp-up {}/{} to {}",
+                self.steps,
+                self.total_steps,
+                format_duration(Duration::from_secs(self.target_seconds))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn date(day: u32) -> NaiveDate {
+        // 2024-09-02 is a Monday
+        NaiveDate::from_ymd_opt(2024, 9, day).unwrap()
+    }
+
+    #[test]
+    fn test_duration_steps_up_to_target() {
+        let ramp_up = RampUp {
+            start: Duration::from_secs(600),
+            step: Duration::from_secs(420),
+            target: Duration::from_secs(1800),
+            ..RampUp::default()
+        };
+
+        assert_eq!(ramp_up.steps(), 3);
+        assert_eq!(ramp_up.duration(0), Duration::from_secs(600));
+        assert_eq!(ramp_up.duration(2), Duration::from_secs(1440));
+        assert_eq!(ramp_up.duration(3), Duration::from_secs(1800));
+        assert_eq!(ramp_up.duration(10), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn test_advance_every_day_of_use() {
+        let state = RampState::new(date(2));
+
+        assert_eq!(state.advance(date(2), Interval::Day).steps, 0);
+
+        let state = state.advance(date(3), Interval::Day);
+        assert_eq!(state.steps, 1);
+        // Days without get-up running do not count
+        let state = state.advance(date(9), Interval::Day);
+        assert_eq!(state.steps, 2);
+        assert_eq!(state.last_day, date(9));
+    }
+
+    #[test]
+    fn test_advance_every_week() {
+        let state = RampState::new(date(2))
+            .advance(date(4), Interval::Week)
+            .advance(date(6), Interval::Week);
+        assert_eq!(state.steps, 0);
+
+        let state = state.advance(date(10), Interval::Week);
+        assert_eq!(state.steps, 1);
+    }
+
+    #[test]
+    fn test_program_persists_state() {
+        let dir = TestDir::new("ramp-up");
+        let store = || Store::new(Some(dir.join(RAMP_UP_FILE_NAME)));
+
+        let program = Program::start(1, RampUp::default(), store(), date(2)).unwrap();
+        assert_eq!(program.duration(), DEFAULT_START);
+
+        let mut program = Program::start(1, RampUp::default(), store(), date(3)).unwrap();
+        assert_eq!(program.duration(), DEFAULT_START + DEFAULT_STEP);
+        assert_eq!(
+            program.progress("standing").describe(),
+            "ramp-up 1/4 to 30m"
+        );
+        assert!(program.advance(date(4)).unwrap());
+        assert!(!program.advance(date(4)).unwrap());
+
+        let program = Program::start(1, RampUp::default(), store(), date(4)).unwrap();
+        assert_eq!(program.state.steps, 2);
+        assert_eq!(program.state.started_on, date(2));
+    }
+}
@@ -0,0 +1,309 @@
+use std::{error::Error, fmt::Write, time::Duration};
+
+use chrono::{Datelike, Days, Local, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    achievements::{format_days, Achievements},
+    cli::ReportArgs,
+    format_duration_hours_minutes,
+    history::{self, History, Record},
+    stats::{self, DailyStats, Span},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportPeriod {
+    /// The last 7 days
+    #[default]
+    Week,
+    /// The last 30 days
+    Month,
+}
+
+impl ReportPeriod {
+    fn days(self) -> u64 {
+        match self {
+            ReportPeriod::Week => 7,
+            ReportPeriod::Month => 30,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub days: Vec<Row>,
+    pub weeks: Vec<Row>,
+    /// Over the whole history rather than the period of the report.
+    pub achievements: Achievements,
+}
+
+/// Totals over a day or a week, the week being labelled by its first day in the report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Row {
+    pub start: NaiveDate,
+    pub sitting_seconds: u64,
+    pub standing_seconds: u64,
+    pub transitions: u32,
+}
+
+impl Row {
+    fn add(&mut self, other: &Row) {
+        self.sitting_seconds += other.sitting_seconds;
+        self.standing_seconds += other.standing_seconds;
+        self.transitions += other.transitions;
+    }
+}
+
+pub fn run(args: &ReportArgs, standing_goal: Option<Duration>) -> Result<(), Box<dyn Error>> {
+    let records = History::new(history::default_path()).load()?;
+    let report = Report::build(
+        &records,
+        Local::now().date_naive(),
+        args.period,
+        standing_goal,
+    );
+
+    print!("{}", report.render(args.format)?);
+
+    Ok(())
+}
+
+impl Report {
+    /// Builds the per-day and per-week totals of the period ending on `today`, along with the
+    /// achievements toward `standing_goal`.
+    pub fn build(
+        records: &[Record],
+        today: NaiveDate,
+        period: ReportPeriod,
+        standing_goal: Option<Duration>,
+    ) -> Self {
+        let spans: Vec<Span> = records.iter().map(Span::from).collect();
+        let first_day = today
+            .checked_sub_days(Days::new(period.days() - 1))
+            .unwrap_or(today);
+
+        let mut report = Report {
+            achievements: Achievements::compute(
+                &stats::standing_by_day(&spans),
+                standing_goal,
+                today,
+            ),
+            ..Report::default()
+        };
+        for date in first_day.iter_days().take_while(|date| *date <= today) {
+            let stats = DailyStats::compute(date, &spans);
+            let row = Row {
+                start: date,
+                sitting_seconds: stats.sitting.as_secs(),
+                standing_seconds: stats.standing.as_secs(),
+                transitions: stats.transitions,
+            };
+
+            match report.weeks.last_mut() {
+                Some(week) if week.start.iso_week() == date.iso_week() => week.add(&row),
+                _ => report.weeks.push(row.clone()),
+            }
+            report.days.push(row);
+        }
+
+        report
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String, Box<dyn Error>> {
+        let output = match format {
+            ReportFormat::Table => self.render_table()?,
+            ReportFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+            ReportFormat::Csv => self.render_csv()?,
+        };
+
+        Ok(output)
+    }
+
+    fn render_table(&self) -> Result<String, std::fmt::Error> {
+        let mut output = String::new();
+
+        for (heading, rows) in [("Day", &self.days), ("Week of", &self.weeks)] {
+            if !output.is_empty() {
+                writeln!(output)?;
+            }
+
+            writeln!(
+                output,
+                "{:<12} {:>9} {:>9} {:>12}",
+                heading, "Sitting", "Standing", "Transitions"
+            )?;
+            for row in rows {
+                writeln!(
+                    output,
+                    "{:<12} {:>9} {:>9} {:>12}",
+                    row.start.format("%a %m-%d").to_string(),
+                    format_duration_hours_minutes(Duration::from_secs(row.sitting_seconds)),
+                    format_duration_hours_minutes(Duration::from_secs(row.standing_seconds)),
+                    row.transitions
+                )?;
+            }
+        }
+
+        writeln!(output)?;
+        self.render_achievements(&mut output)?;
+
+        Ok(output)
+    }
+
+    fn render_achievements(&self, output: &mut String) -> std::fmt::Result {
+        let achievements = &self.achievements;
+
+        if let (Some(current), Some(longest)) =
+            (achievements.current_streak, achievements.longest_streak)
+        {
+            writeln!(output, "{:<16} {}", "Current streak", format_days(current))?;
+            writeln!(output, "{:<16} {}", "Longest streak", format_days(longest))?;
+        }
+        writeln!(
+            output,
+            "{:<16} {}",
+            "Standing total",
+            format_duration_hours_minutes(Duration::from_secs(achievements.standing_seconds))
+        )?;
+        for milestone in &achievements.milestones {
+            writeln!(
+                output,
+                "{:<16} {}h on {}",
+                "Milestone", milestone.hours, milestone.reached_on
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn render_csv(&self) -> Result<String, std::fmt::Error> {
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "period,start,sitting_seconds,standing_seconds,transitions"
+        )?;
+        for (period, rows) in [("day", &self.days), ("week", &self.weeks)] {
+            for row in rows {
+                writeln!(
+                    output,
+                    "{},{},{},{},{}",
+                    period, row.start, row.sitting_seconds, row.standing_seconds, row.transitions
+                )?;
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{history::EndReason, State};
+
+    fn record(day: u32, hour: u32, stance: State) -> Record {
+        Record {
+            start: Local.with_ymd_and_hms(2024, 9, day, hour, 0, 0).unwrap(),
+            end: Local
+                .with_ymd_and_hms(2024, 9, day, hour + 1, 0, 0)
+                .unwrap(),
+            stance,
+            name: stance.name().to_string(),
+            ended_by: EndReason::Timer,
+            paused_seconds: 0,
+            snoozes: 0,
+        }
+    }
+
+    fn report() -> Report {
+        let records = [
+            record(1, 9, State::Sitting),
+            record(2, 9, State::Sitting),
+            record(2, 10, State::Standing),
+        ];
+
+        // 2024-09-01 is a Sunday, the report covers two ISO weeks
+        Report::build(
+            &records,
+            NaiveDate::from_ymd_opt(2024, 9, 3).unwrap(),
+            ReportPeriod::Week,
+            Some(Duration::from_secs(3600)),
+        )
+    }
+
+    #[test]
+    fn test_build_days() {
+        let report = report();
+
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(
+            report.days[0].start,
+            NaiveDate::from_ymd_opt(2024, 8, 28).unwrap()
+        );
+        assert_eq!(report.days[5].sitting_seconds, 3600);
+        assert_eq!(report.days[5].standing_seconds, 3600);
+        assert_eq!(report.days[5].transitions, 1);
+    }
+
+    #[test]
+    fn test_build_weeks() {
+        let report = report();
+
+        assert_eq!(report.weeks.len(), 2);
+        assert_eq!(report.weeks[0].sitting_seconds, 3600);
+        assert_eq!(
+            report.weeks[1].start,
+            NaiveDate::from_ymd_opt(2024, 9, 2).unwrap()
+        );
+        assert_eq!(report.weeks[1].sitting_seconds, 3600);
+        assert_eq!(report.weeks[1].standing_seconds, 3600);
+    }
+
+    #[test]
+    fn test_build_achievements() {
+        let achievements = report().achievements;
+
+        assert_eq!(achievements.current_streak, Some(1));
+        assert_eq!(achievements.longest_streak, Some(1));
+        assert_eq!(achievements.standing_seconds, 3600);
+    }
+
+    #[test]
+    fn test_render_table_achievements() {
+        let table = report().render(ReportFormat::Table).unwrap();
+
+        assert!(table
+            .ends_with("Current streak   1 day\nLongest streak   1 day\nStanding total   1h0m\n"));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = report().render(ReportFormat::Csv).unwrap();
+
+        assert!(csv.starts_with("period,start,sitting_seconds,standing_seconds,transitions\n"));
+        assert!(csv.contains("day,2024-09-02,3600,3600,1\n"));
+        assert!(csv.contains("week,2024-09-02,3600,3600,1\n"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = report().render(ReportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["days"].as_array().unwrap().len(), 7);
+        assert_eq!(value["weeks"][1]["start"], "2024-09-02");
+        assert_eq!(value["achievements"]["longest_streak"], 1);
+    }
+}
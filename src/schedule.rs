@@ -0,0 +1,186 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use serde::Deserialize;
+
+use crate::ReturnPolicy;
+
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Working hours of the week, outside of which the timer is paused.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Schedule {
+    pub enabled: bool,
+    pub days: Vec<Weekday>,
+    pub hours: TimeRange,
+    /// Part of the working hours during which the timer is paused too.
+    pub lunch: Option<TimeRange>,
+    pub on_return: ReturnPolicy,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            hours: TimeRange {
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+                end: NaiveTime::from_hms_opt(17, 30, 0).unwrap_or_default(),
+            },
+            lunch: None,
+            on_return: ReturnPolicy::Reset,
+        }
+    }
+}
+
+/// Hours of a day, such as `"09:00-17:30"`, the end being excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TimeRange {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeRange {
+    fn contains(&self, time: NaiveTime) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+impl TryFrom<String> for TimeRange {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid time range \"{}\", expected HH:MM-HH:MM", text);
+
+        let (start, end) = text.split_once('-').ok_or_else(invalid)?;
+        let start = NaiveTime::parse_from_str(start.trim(), TIME_FORMAT).map_err(|_| invalid())?;
+        let end = NaiveTime::parse_from_str(end.trim(), TIME_FORMAT).map_err(|_| invalid())?;
+
+        if start >= end {
+            return Err(format!(
+                "the time range \"{}\" must end after it starts",
+                text
+            ));
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format(TIME_FORMAT),
+            self.end.format(TIME_FORMAT)
+        )
+    }
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.days.is_empty() {
+            return Err("days must not be empty".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Whether `time` falls within the working hours, outside of the lunch window.
+    pub fn is_working(&self, time: NaiveDateTime) -> bool {
+        self.days.contains(&time.weekday())
+            && self.hours.contains(time.time())
+            && !self.lunch.is_some_and(|lunch| lunch.contains(time.time()))
+    }
+
+    /// When the working hours next start after `time`, at the latest a week later.
+    pub fn next_start(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        let starts = [Some(self.hours.start), self.lunch.map(|lunch| lunch.end)];
+
+        (0..=7)
+            .filter_map(|days| time.date().checked_add_signed(TimeDelta::days(days)))
+            .flat_map(|date| {
+                starts
+                    .into_iter()
+                    .flatten()
+                    .map(move |start| date.and_time(start))
+            })
+            .filter(|start| *start > time && self.is_working(*start))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-09-02 is a Monday
+        NaiveDate::from_ymd_opt(2024, 9, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn schedule() -> Schedule {
+        toml::from_str(
+            r#"
+            enabled = true
+            days = ["mon", "tue", "wed", "thu", "fri"]
+            hours = "09:00-17:30"
+            lunch = "12:00-13:00"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule = schedule();
+
+        assert_eq!(schedule.days.len(), 5);
+        assert_eq!(schedule.hours.to_string(), "09:00-17:30");
+        assert_eq!(schedule.lunch.unwrap().to_string(), "12:00-13:00");
+        assert_eq!(schedule.on_return, ReturnPolicy::Reset);
+    }
+
+    #[test]
+    fn test_parse_invalid_time_range() {
+        for hours in ["9h-17h", "17:30-09:00", "09:00"] {
+            let text = format!("hours = \"{}\"", hours);
+            assert!(toml::from_str::<Schedule>(&text).is_err(), "{}", hours);
+        }
+    }
+
+    #[test]
+    fn test_is_working() {
+        let schedule = schedule();
+
+        assert!(schedule.is_working(at(2, 9, 0)));
+        assert!(schedule.is_working(at(6, 17, 29)));
+        assert!(!schedule.is_working(at(2, 8, 59)));
+        assert!(!schedule.is_working(at(2, 17, 30)));
+        assert!(!schedule.is_working(at(2, 12, 30)));
+        assert!(!schedule.is_working(at(7, 10, 0)));
+    }
+
+    #[test]
+    fn test_next_start() {
+        let schedule = schedule();
+
+        assert_eq!(schedule.next_start(at(2, 7, 0)), Some(at(2, 9, 0)));
+        assert_eq!(schedule.next_start(at(2, 12, 15)), Some(at(2, 13, 0)));
+        assert_eq!(schedule.next_start(at(6, 18, 0)), Some(at(9, 9, 0)));
+    }
+}
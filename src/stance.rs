@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::{duration, State};
+
+pub const SITTING: &str = "sitting";
+pub const STANDING: &str = "standing";
+
+/// A stance of the cycle, such as sitting, standing or walking on a treadmill desk.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stance {
+    pub name: String,
+    /// Whether the stance counts as sitting or standing, in the statistics and for the desk and
+    /// the hooks.
+    pub posture: State,
+    #[serde(deserialize_with = "duration::deserialize_human")]
+    pub duration: Duration,
+    /// Title of the reminder to switch to the stance.
+    pub title: Option<String>,
+    /// Body of the reminder, followed by the time the stance ends.
+    pub message: Option<String>,
+    /// Icon of the desktop notification.
+    pub icon: Option<String>,
+}
+
+impl Stance {
+    /// The sitting and standing stances used when none are configured.
+    pub fn defaults(sitting_duration: Duration, standing_duration: Duration) -> Vec<Self> {
+        vec![
+            Self::new(SITTING, State::Sitting, sitting_duration),
+            Self::new(STANDING, State::Standing, standing_duration),
+        ]
+    }
+
+    pub fn new(name: &str, posture: State, duration: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            posture,
+            duration,
+            title: None,
+            message: None,
+            icon: None,
+        }
+    }
+
+    /// Name of the stance as shown in titles, such as "Standing".
+    pub fn label(&self) -> String {
+        label(&self.name)
+    }
+}
+
+/// Capitalizes the name of a stance, such as "walking" into "Walking".
+pub fn label(name: &str) -> String {
+    let mut characters = name.chars();
+
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stance() {
+        let stance: Stance = toml::from_str(
+            r#"
+            name = "walking"
+            posture = "standing"
+            duration = "20m"
+            title = "Walk!"
+            icon = "/usr/share/icons/walk.png"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(stance.posture, State::Standing);
+        assert_eq!(stance.duration, Duration::from_secs(1200));
+        assert_eq!(stance.title.as_deref(), Some("Walk!"));
+        assert_eq!(stance.message, None);
+        assert_eq!(stance.label(), "Walking");
+    }
+
+    #[test]
+    fn test_parse_stance_requires_posture() {
+        assert!(toml::from_str::<Stance>("name = \"stretch\"\nduration = \"5m\"").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+
+use crate::{history::Record, State};
+
+/// Consecutive periods of the same stance separated by less than this are counted as one stretch.
+const STRETCH_GAP: Duration = Duration::from_secs(60);
+
+/// A completed record or the period in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub stance: State,
+    pub name: String,
+    pub paused: Duration,
+}
+
+impl From<&Record> for Span {
+    fn from(record: &Record) -> Self {
+        Self {
+            start: record.start,
+            end: record.end,
+            stance: record.stance,
+            // The records written before the stances were configurable are named after their
+            // posture
+            name: if record.name.is_empty() {
+                record.stance.name().to_string()
+            } else {
+                record.name.clone()
+            },
+            paused: Duration::from_secs(record.paused_seconds),
+        }
+    }
+}
+
+impl Span {
+    /// Time spent between `from` and `to`, less a proportional share of the paused time.
+    fn active_between(&self, from: DateTime<Local>, to: DateTime<Local>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.min(to);
+        let Ok(clipped) = (end - start).to_std() else {
+            return Duration::ZERO;
+        };
+        let Ok(total) = (self.end - self.start).to_std() else {
+            return Duration::ZERO;
+        };
+
+        if total.is_zero() {
+            return Duration::ZERO;
+        }
+
+        let paused = self
+            .paused
+            .min(total)
+            .mul_f64(clipped.as_secs_f64() / total.as_secs_f64());
+        clipped.saturating_sub(paused)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DailyStats {
+    pub sitting: Duration,
+    pub standing: Duration,
+    pub transitions: u32,
+    pub longest_sitting: Duration,
+}
+
+impl DailyStats {
+    /// Computes the totals of `date` from spans sorted by start time.
+    pub fn compute(date: NaiveDate, spans: &[Span]) -> Self {
+        let (from, to) = day_bounds(date);
+        let mut stats = DailyStats::default();
+        let mut previous: Option<&Span> = None;
+        let mut sitting_stretch = Duration::ZERO;
+
+        for span in spans {
+            let active = span.active_between(from, to);
+            let in_day = span.end > from && span.start < to;
+
+            let continues_stretch = previous.is_some_and(|previous| {
+                previous.name == span.name
+                    && (span.start - previous.end).to_std().unwrap_or_default() < STRETCH_GAP
+            });
+            let changed_stance = previous.is_some_and(|previous| previous.name != span.name);
+
+            if in_day && changed_stance && span.start >= from {
+                stats.transitions += 1;
+            }
+
+            match span.stance {
+                State::Sitting => {
+                    stats.sitting += active;
+                    sitting_stretch = if continues_stretch {
+                        sitting_stretch + active
+                    } else {
+                        active
+                    };
+                    stats.longest_sitting = stats.longest_sitting.max(sitting_stretch);
+                }
+                State::Standing => stats.standing += active,
+            }
+
+            previous = Some(span);
+        }
+
+        stats
+    }
+
+    /// Number of sitting minutes for every standing minute.
+    pub fn sit_stand_ratio(&self) -> Option<f64> {
+        if self.standing.is_zero() {
+            return None;
+        }
+
+        Some(self.sitting.as_secs_f64() / self.standing.as_secs_f64())
+    }
+}
+
+/// Standing time of every day, a span over midnight counting toward both days.
+pub fn standing_by_day(spans: &[Span]) -> BTreeMap<NaiveDate, Duration> {
+    let mut days = BTreeMap::new();
+
+    for span in spans.iter().filter(|span| span.stance == State::Standing) {
+        for date in span
+            .start
+            .date_naive()
+            .iter_days()
+            .take_while(|date| *date <= span.end.date_naive())
+        {
+            let (from, to) = day_bounds(date);
+            *days.entry(date).or_default() += span.active_between(from, to);
+        }
+    }
+
+    days
+}
+
+pub fn day_bounds(date: NaiveDate) -> (DateTime<Local>, DateTime<Local>) {
+    let start_of = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .earliest()
+            .unwrap_or_default()
+    };
+
+    (start_of(date), start_of(date.succ_opt().unwrap_or(date)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{history::EndReason, stance};
+
+    fn span(stance: State, start: (u32, u32), end: (u32, u32), paused: u64) -> Span {
+        let at = |(hour, minute): (u32, u32)| {
+            Local.with_ymd_and_hms(2024, 9, 2, hour, minute, 0).unwrap()
+        };
+
+        Span {
+            start: at(start),
+            end: at(end),
+            stance,
+            name: stance.name().to_string(),
+            paused: Duration::from_secs(paused),
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 9, 2).unwrap()
+    }
+
+    #[test]
+    fn test_compute_totals() {
+        let spans = [
+            span(State::Sitting, (9, 0), (10, 0), 0),
+            span(State::Standing, (10, 0), (10, 30), 0),
+            span(State::Sitting, (10, 30), (11, 0), 600),
+        ];
+
+        let stats = DailyStats::compute(date(), &spans);
+
+        assert_eq!(stats.sitting, Duration::from_secs(5400 - 600));
+        assert_eq!(stats.standing, Duration::from_secs(1800));
+        assert_eq!(stats.transitions, 2);
+        assert_eq!(stats.longest_sitting, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_compute_merges_contiguous_sitting_stretch() {
+        let spans = [
+            span(State::Sitting, (9, 0), (10, 0), 0),
+            span(State::Sitting, (10, 0), (10, 20), 0),
+        ];
+
+        let stats = DailyStats::compute(date(), &spans);
+
+        assert_eq!(stats.transitions, 0);
+        assert_eq!(stats.longest_sitting, Duration::from_secs(4800));
+    }
+
+    #[test]
+    fn test_compute_tells_stances_of_a_posture_apart() {
+        let kneeling = Span {
+            name: "kneeling".to_string(),
+            ..span(State::Sitting, (10, 0), (10, 20), 0)
+        };
+        let spans = [span(State::Sitting, (9, 0), (10, 0), 0), kneeling];
+
+        let stats = DailyStats::compute(date(), &spans);
+
+        assert_eq!(stats.sitting, Duration::from_secs(4800));
+        assert_eq!(stats.transitions, 1);
+        assert_eq!(stats.longest_sitting, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_span_of_unnamed_record() {
+        let record = Record {
+            start: Local.with_ymd_and_hms(2024, 9, 2, 9, 0, 0).unwrap(),
+            end: Local.with_ymd_and_hms(2024, 9, 2, 10, 0, 0).unwrap(),
+            stance: State::Standing,
+            name: String::new(),
+            ended_by: EndReason::Timer,
+            paused_seconds: 0,
+            snoozes: 0,
+        };
+
+        assert_eq!(Span::from(&record).name, stance::STANDING);
+    }
+
+    #[test]
+    fn test_compute_ignores_other_days() {
+        let spans = [span(State::Sitting, (9, 0), (10, 0), 0)];
+
+        let stats = DailyStats::compute(date().succ_opt().unwrap(), &spans);
+
+        assert_eq!(stats, DailyStats::default());
+    }
+
+    #[test]
+    fn test_standing_by_day_splits_at_midnight() {
+        let spans = [
+            span(State::Standing, (10, 0), (10, 30), 0),
+            Span {
+                start: Local.with_ymd_and_hms(2024, 9, 2, 23, 30, 0).unwrap(),
+                end: Local.with_ymd_and_hms(2024, 9, 3, 0, 15, 0).unwrap(),
+                stance: State::Standing,
+                name: stance::STANDING.to_string(),
+                paused: Duration::ZERO,
+            },
+            span(State::Sitting, (11, 0), (12, 0), 0),
+        ];
+
+        let days = standing_by_day(&spans);
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[&date()], Duration::from_secs(3600));
+        assert_eq!(days[&date().succ_opt().unwrap()], Duration::from_secs(900));
+    }
+
+    #[test]
+    fn test_sit_stand_ratio() {
+        let stats = DailyStats {
+            sitting: Duration::from_secs(3600),
+            standing: Duration::from_secs(1800),
+            ..DailyStats::default()
+        };
+
+        assert_eq!(stats.sit_stand_ratio(), Some(2.0));
+        assert_eq!(DailyStats::default().sit_stand_ratio(), None);
+    }
+}
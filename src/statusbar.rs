@@ -0,0 +1,269 @@
+use std::{
+    error::Error,
+    io::{self, Write},
+    thread,
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    cli::StatusArgs,
+    ctl,
+    daemon::{self, Client, Reply, Request, Status},
+    format_duration_hours_minutes_seconds, format_time_after_duration, stance, POLL_DURATION,
+};
+
+const BLOCK_NAME: &str = "get-up";
+const IN_PROGRESS_COLOR: &str = "#00FF00";
+const PAUSED_COLOR: &str = "#FFFF00";
+const WAITING_COLOR: &str = "#FF5555";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFormat {
+    /// JSON object with text, tooltip and class, for waybar custom modules
+    Waybar,
+    /// Blocks of the i3bar protocol, for i3bar and swaybar
+    I3bar,
+    /// A line of text, for polybar, i3blocks and similar
+    #[default]
+    Plain,
+}
+
+/// What a status bar shows of the daemon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    pub tooltip: String,
+    pub classes: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct WaybarLine<'a> {
+    text: &'a str,
+    tooltip: &'a str,
+    class: &'a [&'static str],
+}
+
+#[derive(Debug, Serialize)]
+struct I3barBlock<'a> {
+    name: &'static str,
+    full_text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'static str>,
+}
+
+pub fn run(args: &StatusArgs) -> Result<(), Box<dyn Error>> {
+    let path =
+        daemon::socket_path().ok_or("could not locate the control socket, HOME is not set")?;
+    let mut stdout = io::stdout().lock();
+    let mut client = None;
+
+    if args.format == StatusFormat::I3bar {
+        writeln!(stdout, "{{\"version\":1}}\n[")?;
+    }
+
+    for tick in 0.. {
+        if client.is_none() {
+            client = Client::connect(&path).ok();
+        }
+
+        let status = match client.as_mut().map(|client| client.send(&Request::Status)) {
+            Some(Ok(Reply::Status(status))) => Some(status),
+            _ => {
+                client = None;
+                None
+            }
+        };
+
+        if status.is_none() && !args.follow {
+            return Err(format!("no daemon is listening on {}", path.display()).into());
+        }
+
+        let rendered = render(args.format, &Line::new(status.as_ref()))?;
+        if args.format == StatusFormat::I3bar && tick > 0 {
+            write!(stdout, ",")?;
+        }
+        writeln!(stdout, "{}", rendered)?;
+        stdout.flush()?;
+
+        if !args.follow {
+            break;
+        }
+        thread::sleep(POLL_DURATION);
+    }
+
+    Ok(())
+}
+
+impl Line {
+    /// Line for `status`, `None` meaning that the daemon is not running.
+    pub fn new(status: Option<&Status>) -> Self {
+        let Some(status) = status else {
+            return Self {
+                text: String::new(),
+                tooltip: "get-up is not running".to_string(),
+                classes: vec!["stopped"],
+            };
+        };
+
+        let name = stance::label(&status.stance);
+        let time_left = status.time_left();
+
+        let (text, tooltip) = match &status.awaiting {
+            Some(stance) => (
+                format!("Switch to {}!", stance),
+                format!("Waiting for you to switch to {}", stance),
+            ),
+            None => (
+                format!(
+                    "{} {}",
+                    name,
+                    format_duration_hours_minutes_seconds(time_left)
+                ),
+                format!("{} until {}", name, format_time_after_duration(time_left)),
+            ),
+        };
+
+        let mut classes = vec![status.posture.name()];
+        let label = if status.awaiting.is_some() {
+            classes.push("waiting");
+            None
+        } else if status.paused {
+            classes.push("paused");
+            Some("[PAUSED]")
+        } else if let Some(hold) = status.holds.first() {
+            classes.push("paused");
+            Some(hold.label())
+        } else {
+            None
+        };
+
+        match label {
+            // The end time is unknown while paused
+            Some(label) => Self {
+                text: format!("{} {}", text, label),
+                tooltip: ctl::describe(status),
+                classes,
+            },
+            None => Self {
+                text,
+                tooltip,
+                classes,
+            },
+        }
+    }
+
+    fn color(&self) -> Option<&'static str> {
+        if self.classes.contains(&"waiting") {
+            Some(WAITING_COLOR)
+        } else if self.classes.contains(&"paused") {
+            Some(PAUSED_COLOR)
+        } else if self.classes.contains(&"stopped") {
+            None
+        } else {
+            Some(IN_PROGRESS_COLOR)
+        }
+    }
+}
+
+pub fn render(format: StatusFormat, line: &Line) -> serde_json::Result<String> {
+    match format {
+        StatusFormat::Waybar => serde_json::to_string(&WaybarLine {
+            text: &line.text,
+            tooltip: &line.tooltip,
+            class: &line.classes,
+        }),
+        StatusFormat::I3bar => serde_json::to_string(&[I3barBlock {
+            name: BLOCK_NAME,
+            full_text: &line.text,
+            color: line.color(),
+        }]),
+        StatusFormat::Plain => Ok(line.text.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Local;
+
+    use super::*;
+    use crate::{Hold, State};
+
+    fn status() -> Status {
+        Status {
+            stance: "sitting".to_string(),
+            posture: State::Sitting,
+            paused: false,
+            holds: Vec::new(),
+            awaiting: None,
+            snoozed: false,
+            elapsed_seconds: 600,
+            duration_seconds: 3600,
+            durations_seconds: BTreeMap::from([
+                ("sitting".to_string(), 3600),
+                ("standing".to_string(), 1800),
+            ]),
+            period_start: Local::now(),
+            period_paused_seconds: 0,
+            warning: None,
+            pomodoro: None,
+            ramp_up: None,
+        }
+    }
+
+    #[test]
+    fn test_render_waybar() {
+        let line = Line::new(Some(&status()));
+        let json = render(StatusFormat::Waybar, &line).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["text"], "Sitting 0h50m0s");
+        assert!(value["tooltip"]
+            .as_str()
+            .unwrap()
+            .starts_with("Sitting until "));
+        assert_eq!(value["class"], serde_json::json!(["sitting"]));
+    }
+
+    #[test]
+    fn test_render_i3bar_paused() {
+        let mut status = status();
+        status.holds = vec![Hold::Locked];
+
+        let json = render(StatusFormat::I3bar, &Line::new(Some(&status))).unwrap();
+
+        assert_eq!(
+            json,
+            "[{\"name\":\"get-up\",\"full_text\":\"Sitting 0h50m0s [LOCKED]\",\"color\":\"#FFFF00\"}]"
+        );
+        assert_eq!(
+            Line::new(Some(&status)).tooltip,
+            "Sitting, 0h50m0s left [LOCKED]"
+        );
+    }
+
+    #[test]
+    fn test_render_plain_waiting() {
+        let status = Status {
+            awaiting: Some("standing".to_string()),
+            ..status()
+        };
+
+        assert_eq!(
+            render(StatusFormat::Plain, &Line::new(Some(&status))).unwrap(),
+            "Switch to standing!"
+        );
+    }
+
+    #[test]
+    fn test_line_without_daemon() {
+        let line = Line::new(None);
+
+        assert_eq!(line.text, "");
+        assert_eq!(line.classes, vec!["stopped"]);
+        assert_eq!(line.color(), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+};
+
+use zbus::blocking::{connection::Builder, Connection};
+
+/// Private `dbus-daemon` standing in for the session or system bus in tests, killed when dropped.
+///
+/// The tests using it are ignored by default, run them with `cargo test -- --ignored`.
+pub struct TestBus {
+    daemon: Child,
+    address: String,
+}
+
+impl TestBus {
+    /// Starts a bus, panicking when `dbus-daemon` is not installed.
+    pub fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon is needed to run the D-Bus tests");
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+
+        Self {
+            daemon,
+            address: address.trim().to_string(),
+        }
+    }
+
+    pub fn connect(&self) -> Connection {
+        Builder::address(self.address.as_str())
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    /// Connects and owns `name`, as the service under test or the one it talks to.
+    pub fn connect_as(&self, name: &'static str) -> Connection {
+        Builder::address(self.address.as_str())
+            .unwrap()
+            .name(name)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
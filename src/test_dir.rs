@@ -0,0 +1,32 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Directory of the system temporary directory for the files of a test, removed when dropped so
+/// that a failing test cleans up too.
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    /// Reserves a directory named after `name` and the process, left for the code under test to
+    /// create.
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("get-up-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+
+        Self { path }
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}